@@ -0,0 +1,158 @@
+// Batch verification of subvector openings, possibly against different commitments.
+//
+// Every opening k satisfies e(c_k/g^R_k(τ), g) = e(π_k, g^A_k(τ)).
+// With random r_k all of them are checked by a single multi-pairing
+//   e(∏ (c_k/g^R_k(τ))^(r_k), g) · ∏ e(π_k^(-r_k), g^A_k(τ)) = 1
+// which a wrong opening passes only with probability 1/|Fr|.
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use ark_std::rand::Rng;
+
+use ark_std::ops::{Neg, SubAssign};
+use ark_std::vec::Vec;
+
+use crate::{opening_terms, Commitment, CustomError, Proof, Roots, Transcript, VerifyingKey};
+
+struct BatchItem<E: PairingEngine> {
+  commit: Commitment<E>,
+  point_values: Vec<E::Fr>,
+  points: Vec<u32>,
+  proof: Proof<E>,
+}
+
+pub struct BatchVerifier<E: PairingEngine> {
+  items: Vec<BatchItem<E>>,
+}
+
+impl<E: PairingEngine> Default for BatchVerifier<E> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<E: PairingEngine> BatchVerifier<E> {
+  pub fn new() -> Self {
+    BatchVerifier { items: Vec::new() }
+  }
+
+  // same arguments as verify_pos
+  pub fn add(
+    &mut self,
+    commit: &Commitment<E>,
+    point_values: Vec<E::Fr>,
+    points: Vec<u32>,
+    proof: &Proof<E>,
+  ) {
+    self.items.push(BatchItem {
+      commit: commit.clone(),
+      point_values,
+      points,
+      proof: proof.clone(),
+    });
+  }
+
+  pub fn len(&self) -> usize {
+    self.items.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.items.is_empty()
+  }
+
+  // Returns the positions (in order of add) of the openings that don't verify;
+  // an empty list means the whole batch is valid.
+  // Malformed items (bad index sets, length mismatches) are errors, as in verify_pos.
+  pub fn verify<R: Rng>(
+    &self,
+    vrk_params: &VerifyingKey<E>,
+    omega: E::Fr,
+    rng: &mut R,
+  ) -> Result<Vec<usize>, CustomError> {
+    self.verify_with_roots(vrk_params, Roots::Omega(omega), rng)
+  }
+
+  // verify with the r_k drawn from a transcript after it absorbs every opening,
+  // so the batch check needs no randomness of its own
+  pub fn verify_with_transcript(
+    &self,
+    vrk_params: &VerifyingKey<E>,
+    omega: E::Fr,
+    transcript: &mut Transcript,
+  ) -> Result<Vec<usize>, CustomError> {
+    self.verify_with_roots_transcript(vrk_params, Roots::Omega(omega), transcript)
+  }
+
+  pub(crate) fn verify_with_roots_transcript(
+    &self,
+    vrk_params: &VerifyingKey<E>,
+    roots: Roots<E::Fr>,
+    transcript: &mut Transcript,
+  ) -> Result<Vec<usize>, CustomError> {
+    transcript.append(b"openings", &(self.items.len() as u64))?;
+    for item in self.items.iter() {
+      transcript.append(b"commitment", &item.commit)?;
+      transcript.append(b"points", &item.points)?;
+      transcript.append(b"values", &item.point_values)?;
+      transcript.append(b"proof", &item.proof)?;
+    }
+    let scalars = transcript.challenge_scalars(b"batch", self.items.len());
+    self.verify_with_scalars(vrk_params, roots, &scalars)
+  }
+
+  pub(crate) fn verify_with_roots<R: Rng>(
+    &self,
+    vrk_params: &VerifyingKey<E>,
+    roots: Roots<E::Fr>,
+    rng: &mut R,
+  ) -> Result<Vec<usize>, CustomError> {
+    let scalars: Vec<E::Fr> = self.items.iter().map(|_| E::Fr::rand(rng)).collect();
+    self.verify_with_scalars(vrk_params, roots, &scalars)
+  }
+
+  fn verify_with_scalars(
+    &self,
+    vrk_params: &VerifyingKey<E>,
+    roots: Roots<E::Fr>,
+    scalars: &[E::Fr],
+  ) -> Result<Vec<usize>, CustomError> {
+    if self.items.is_empty() {
+      return Ok(Vec::new());
+    }
+
+    // c_k/g^R_k(τ) and g^A_k(τ) of every opening
+    let mut terms = Vec::with_capacity(self.items.len());
+    for item in self.items.iter() {
+      let (r_value, a_value) =
+        opening_terms(vrk_params, &item.point_values, &item.points, roots)?;
+      let mut inner = item.commit.commit.into_projective();
+      inner.sub_assign(&r_value);
+      terms.push((inner, a_value.into_affine()));
+    }
+
+    let mut lhs = E::G1Projective::zero();
+    let mut pairs = Vec::with_capacity(self.items.len() + 1);
+    for ((item, (inner, a_value)), r_k) in self.items.iter().zip(terms.iter()).zip(scalars.iter()) {
+      lhs += &inner.mul(r_k.into_repr());
+      pairs.push((item.proof.w.mul(r_k.neg()).into_affine().into(), (*a_value).into()));
+    }
+    pairs.push((lhs.into_affine().into(), vrk_params.list_g2_tau_i[0].into()));
+
+    if E::product_of_pairings(pairs.iter()).is_one() {
+      return Ok(Vec::new());
+    }
+
+    // rejected: find the culprits one by one
+    let g2 = vrk_params.list_g2_tau_i[0];
+    let failed = self
+      .items
+      .iter()
+      .zip(terms.iter())
+      .enumerate()
+      .filter(|(_, (item, (inner, a_value)))| {
+        E::pairing(*inner, g2) != E::pairing(item.proof.w, *a_value)
+      })
+      .map(|(k, _)| k)
+      .collect();
+    Ok(failed)
+  }
+}
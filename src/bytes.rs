@@ -0,0 +1,160 @@
+// Commitments to byte blobs.
+//
+// The blob is packed little-endian into elements of k = ⌊(|Fr| - 1)/8⌋ bytes,
+// 31 for BLS12-381, so every chunk is below the modulus and unpacks exactly.
+// Position 0 holds the blob length and the chunks follow from position 1, so
+// blobs that differ only in trailing zero bytes get different commitments.
+// A byte range opens position 0 and the positions of the chunks covering it; the
+// proof carries the length and those chunks, and the verifier checks the range
+// against the length and its bytes against the matching slice.
+use ark_ec::PairingEngine;
+use ark_ff::{BigInteger, PrimeField};
+use ark_poly::GeneralEvaluationDomain;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+
+use ark_std::ops::Range;
+use ark_std::{vec, vec::Vec};
+
+use crate::{
+  commit, group_gen, new_domain, prove_pos_with, verify_pos_with, Commitment, CustomError, Proof,
+  ProvingKey, Roots, VerifyingKey,
+};
+
+// the blob length, the covering chunks and π_I for position 0 and theirs
+#[derive(Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ByteRangeProof<E: PairingEngine> {
+  pub len: u64,
+  pub chunks: Vec<E::Fr>,
+  pub proof: Proof<E>,
+}
+
+// k, the bytes packed into one element
+pub fn bytes_per_element<F: PrimeField>() -> usize {
+  (F::size_in_bits() - 1) / 8
+}
+
+// ⌈len/k⌉ elements, the last one zero padded
+pub fn pack<F: PrimeField>(data: &[u8]) -> Vec<F> {
+  data.chunks(bytes_per_element::<F>()).map(F::from_le_bytes_mod_order).collect()
+}
+
+// k bytes per element; None if one of them is not a packed chunk
+pub fn unpack<F: PrimeField>(values: &[F]) -> Option<Vec<u8>> {
+  let mut data = Vec::with_capacity(values.len() * bytes_per_element::<F>());
+  for value in values.iter() {
+    data.extend_from_slice(&unpack_chunk(value)?);
+  }
+  Some(data)
+}
+
+// k bytes of a chunk, None if the element is not a packed chunk
+fn unpack_chunk<F: PrimeField>(chunk: &F) -> Option<Vec<u8>> {
+  let k = bytes_per_element::<F>();
+  let mut bytes = chunk.into_repr().to_bytes_le();
+  if bytes[k..].iter().any(|b| *b != 0) {
+    return None;
+  }
+  bytes.truncate(k);
+  Some(bytes)
+}
+
+// the committed vector: the length, then the packed chunks
+fn blob_values<F: PrimeField>(data: &[u8]) -> Vec<F> {
+  let mut values = Vec::with_capacity(1 + data.len() / bytes_per_element::<F>() + 1);
+  values.push(F::from(data.len() as u64));
+  values.extend(pack::<F>(data));
+  values
+}
+
+// position 0 and the positions of the chunks covering a non-empty byte range
+fn covering_points<F: PrimeField>(range: &Range<usize>) -> Result<Vec<u32>, CustomError> {
+  let k = bytes_per_element::<F>();
+  let last = (range.end - 1) / k + 1;
+  // beyond the last addressable position
+  if last > u32::MAX as usize {
+    let len = (u32::MAX as usize).saturating_mul(k);
+    return Err(CustomError::InvalidByteRange { start: range.start, end: range.end, len });
+  }
+  let mut points = vec![0];
+  points.extend((range.start / k + 1..last + 1).map(|i| i as u32));
+  Ok(points)
+}
+
+pub fn commit_bytes<E: PairingEngine>(
+  prk_params: &ProvingKey<E>,
+  data: &[u8],
+) -> Result<Commitment<E>, CustomError> {
+  commit(prk_params, blob_values(data))
+}
+
+// opens data[range]
+pub fn prove_byte_range<E: PairingEngine>(
+  prk_params: &ProvingKey<E>,
+  data: &[u8],
+  range: Range<usize>,
+) -> Result<ByteRangeProof<E>, CustomError> {
+  let n = prk_params.list_l_i.len();
+  let domain: GeneralEvaluationDomain<E::Fr> = new_domain(n)?;
+  prove_byte_range_with(prk_params, data, range, &domain, Roots::Omega(group_gen::<E>(&domain)))
+}
+
+pub(crate) fn prove_byte_range_with<E: PairingEngine>(
+  prk_params: &ProvingKey<E>,
+  data: &[u8],
+  range: Range<usize>,
+  domain: &GeneralEvaluationDomain<E::Fr>,
+  roots: Roots<E::Fr>,
+) -> Result<ByteRangeProof<E>, CustomError> {
+  if range.start >= range.end || range.end > data.len() {
+    return Err(CustomError::InvalidByteRange { start: range.start, end: range.end, len: data.len() });
+  }
+  let values = blob_values::<E::Fr>(data);
+  let points = covering_points::<E::Fr>(&range)?;
+  let chunks = points[1..].iter().map(|i| values[*i as usize]).collect();
+  let proof = prove_pos_with(prk_params, values, &points, domain, roots)?;
+
+  Ok(ByteRangeProof { len: data.len() as u64, chunks, proof })
+}
+
+// checks that bytes sit at offset start of the committed blob, inside its length
+pub fn verify_byte_range<E: PairingEngine>(
+  vrk_params: &VerifyingKey<E>,
+  commit: &Commitment<E>,
+  start: usize,
+  bytes: &[u8],
+  proof: &ByteRangeProof<E>,
+  omega: E::Fr,
+) -> Result<bool, CustomError> {
+  verify_byte_range_with(vrk_params, commit, start, bytes, proof, Roots::Omega(omega))
+}
+
+pub(crate) fn verify_byte_range_with<E: PairingEngine>(
+  vrk_params: &VerifyingKey<E>,
+  commit: &Commitment<E>,
+  start: usize,
+  bytes: &[u8],
+  proof: &ByteRangeProof<E>,
+  roots: Roots<E::Fr>,
+) -> Result<bool, CustomError> {
+  let end = start.checked_add(bytes.len()).filter(|end| *end > start);
+  let range = start..end.ok_or(CustomError::InvalidByteRange { start, end: start, len: 0 })?;
+  let points = covering_points::<E::Fr>(&range)?;
+  if proof.chunks.len() + 1 != points.len() || range.end as u64 > proof.len {
+    return Ok(false);
+  }
+
+  // the unpacked chunks start at byte k·(points[1] - 1)
+  let unpacked = match unpack(&proof.chunks) {
+    Some(unpacked) => unpacked,
+    None => return Ok(false),
+  };
+  let offset = start % bytes_per_element::<E::Fr>();
+  if unpacked[offset..offset + bytes.len()] != *bytes {
+    return Ok(false);
+  }
+
+  let mut point_values = Vec::with_capacity(points.len());
+  point_values.push(E::Fr::from(proof.len));
+  point_values.extend_from_slice(&proof.chunks);
+  verify_pos_with(vrk_params, commit, &point_values, &points, &proof.proof, roots)
+}
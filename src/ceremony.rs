@@ -0,0 +1,328 @@
+// Multi-party powers-of-tau setup.
+//
+// The powers start at τ = 1. Participant k samples a secret s_k and moves every
+// g^(τ^i) to g^((τ·s_k)^i), so the final τ = ∏ s_k is unknown unless every
+// participant colludes. Each step carries
+// - g^(s_k), g2^(s_k) and a Schnorr proof of knowledge of s_k, bound to the
+//   powers it extends, so nobody can cancel earlier contributions
+// - the pairing checks e(g^(τ'), g2) = e(g^τ, g2^(s_k)) linking new and old powers
+// and anyone can replay the whole transcript with Ceremony::verify.
+use ark_ec::msm::VariableBaseMSM;
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use ark_poly::EvaluationDomain;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::rand::Rng;
+
+use ark_std::convert::TryFrom;
+use ark_std::ops::{MulAssign, Neg};
+use ark_std::{vec, vec::Vec};
+
+use crate::{hash_to_scalars, new_domain, CustomError, Parameters};
+
+// g^(τ^i), g2^(τ^i), i ∈ [0, n]
+#[derive(Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Powers<E: PairingEngine> {
+  pub list_g1_tau_i: Vec<E::G1Affine>,
+  pub list_g2_tau_i: Vec<E::G2Affine>,
+}
+
+// g^s, g2^s and a Schnorr proof (r = g^k, z = k + c·s) of knowledge of s
+#[derive(Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ContributionProof<E: PairingEngine> {
+  pub s_g1: E::G1Affine,
+  pub s_g2: E::G2Affine,
+  pub r: E::G1Affine,
+  pub z: E::Fr,
+}
+
+#[derive(Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Contribution<E: PairingEngine> {
+  pub powers: Powers<E>,
+  pub proof: ContributionProof<E>,
+}
+
+// the transcript: n and every contribution in order
+#[derive(Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Ceremony<E: PairingEngine> {
+  size: u64,
+  contributions: Vec<Contribution<E>>,
+}
+
+impl<E: PairingEngine> Powers<E> {
+  // τ = 1: n+1 copies of the generators
+  pub fn new(n: usize) -> Result<Self, CustomError> {
+    check_size::<E>(n)?;
+    Ok(Powers {
+      list_g1_tau_i: vec![E::G1Affine::prime_subgroup_generator(); n + 1],
+      list_g2_tau_i: vec![E::G2Affine::prime_subgroup_generator(); n + 1],
+    })
+  }
+
+  // n
+  pub fn size(&self) -> usize {
+    self.list_g1_tau_i.len() - 1
+  }
+
+  // one participant's step: τ' = τ·s for a fresh secret s that is dropped afterwards
+  pub fn contribute<R: Rng>(&self, rng: &mut R) -> Result<Contribution<E>, CustomError> {
+    self.check_shape()?;
+    let s = E::Fr::rand(rng);
+    let g1 = self.list_g1_tau_i[0];
+    let g2 = self.list_g2_tau_i[0];
+
+    // s^i
+    let mut s_i = E::Fr::one();
+    let mut list_g1_tau_i = Vec::with_capacity(self.list_g1_tau_i.len());
+    let mut list_g2_tau_i = Vec::with_capacity(self.list_g2_tau_i.len());
+    for (g1_i, g2_i) in self.list_g1_tau_i.iter().zip(self.list_g2_tau_i.iter()) {
+      list_g1_tau_i.push(g1_i.mul(s_i));
+      list_g2_tau_i.push(g2_i.mul(s_i));
+      s_i.mul_assign(&s);
+    }
+
+    let s_g1 = g1.mul(s).into_affine();
+    let s_g2 = g2.mul(s).into_affine();
+    let k = E::Fr::rand(rng);
+    let r = g1.mul(k).into_affine();
+    let c = self.challenge(&s_g1, &s_g2, &r)?;
+
+    Ok(Contribution {
+      powers: Powers {
+        list_g1_tau_i: E::G1Projective::batch_normalization_into_affine(&list_g1_tau_i),
+        list_g2_tau_i: E::G2Projective::batch_normalization_into_affine(&list_g2_tau_i),
+      },
+      proof: ContributionProof { s_g1, s_g2, r, z: k + c * s },
+    })
+  }
+
+  // n+1 powers in both groups, n ≥ 1
+  fn check_shape(&self) -> Result<(), CustomError> {
+    if self.list_g1_tau_i.len() < 2 || self.list_g1_tau_i.len() != self.list_g2_tau_i.len() {
+      return Err(CustomError::MalformedKey);
+    }
+    Ok(())
+  }
+
+  // c = H(g^τ, g2^τ, g^s, g2^s, r): the proof only extends these powers
+  fn challenge(
+    &self,
+    s_g1: &E::G1Affine,
+    s_g2: &E::G2Affine,
+    r: &E::G1Affine,
+  ) -> Result<E::Fr, CustomError> {
+    let mut statement = b"asvc-powers-of-tau-contribution".to_vec();
+    self.list_g1_tau_i[1].serialize(&mut statement)?;
+    self.list_g2_tau_i[1].serialize(&mut statement)?;
+    s_g1.serialize(&mut statement)?;
+    s_g2.serialize(&mut statement)?;
+    r.serialize(&mut statement)?;
+    Ok(hash_to_scalars(&statement, 1)[0])
+  }
+
+  // g^(τ^i) and g2^(τ^i) are powers of one τ ≠ 0:
+  //   e(∑ ρ_i g^(τ^(i+1)), g2) = e(∑ ρ_i g^(τ^i), g2^τ)
+  //   e(g^τ, ∑ ρ_j g2^(τ^j)) = e(g, ∑ ρ_j g2^(τ^(j+1)))
+  // with ρ hashed from the powers, so one pairing pair per list checks all of them
+  pub(crate) fn check(&self) -> Result<bool, CustomError> {
+    let (g1, g2) = (&self.list_g1_tau_i, &self.list_g2_tau_i);
+    if g1.len() < 2 || g2.len() < 2 {
+      return Err(CustomError::MalformedKey);
+    }
+    if g1[1].is_zero() || g2[1].is_zero() {
+      return Ok(false);
+    }
+
+    let mut statement = b"asvc-powers-of-tau-check".to_vec();
+    self.serialize(&mut statement)?;
+    let rho = hash_to_scalars::<E::Fr>(&statement, g1.len().max(g2.len()) - 1);
+
+    Ok(check_g1_powers::<E>(g1, &g2[0], &g2[1], &rho)
+      && check_g2_powers::<E>(&g1[0], &g1[1], g2, &rho))
+  }
+}
+
+// P_i = P_0^(τ^i) for every i, given g2 and g2^τ:
+//   e(∑ ρ_i P_(i+1), g2) = e(∑ ρ_i P_i, g2^τ)
+pub(crate) fn check_g1_powers<E: PairingEngine>(
+  powers: &[E::G1Affine],
+  g2: &E::G2Affine,
+  g2_tau: &E::G2Affine,
+  rho: &[E::Fr],
+) -> bool {
+  if powers.len() < 2 {
+    return true;
+  }
+  let lhs = linear_combination(&powers[1..], rho);
+  let rhs = linear_combination(&powers[..powers.len() - 1], rho);
+  E::product_of_pairings(
+    [(lhs.into_affine().into(), (*g2).into()), (rhs.into_affine().neg().into(), (*g2_tau).into())]
+      .iter(),
+  )
+  .is_one()
+}
+
+// Q_i = Q_0^(τ^i) for every i, given g and g^τ:
+//   e(g^τ, ∑ ρ_i Q_i) = e(g, ∑ ρ_i Q_(i+1))
+pub(crate) fn check_g2_powers<E: PairingEngine>(
+  g1: &E::G1Affine,
+  g1_tau: &E::G1Affine,
+  powers: &[E::G2Affine],
+  rho: &[E::Fr],
+) -> bool {
+  if powers.len() < 2 {
+    return true;
+  }
+  let lhs = linear_combination(&powers[..powers.len() - 1], rho);
+  let rhs = linear_combination(&powers[1..], rho);
+  E::product_of_pairings(
+    [((*g1_tau).into(), lhs.into_affine().into()), (g1.neg().into(), rhs.into_affine().into())]
+      .iter(),
+  )
+  .is_one()
+}
+
+// ∑ ρ_i P_i
+pub(crate) fn linear_combination<G: AffineCurve>(bases: &[G], rho: &[G::ScalarField]) -> G::Projective {
+  let scalars: Vec<<G::ScalarField as PrimeField>::BigInt> =
+    rho.iter().take(bases.len()).map(|r| r.into_repr()).collect();
+  VariableBaseMSM::multi_scalar_mul(bases, &scalars)
+}
+
+impl<E: PairingEngine> Contribution<E> {
+  // checks this step against the powers it claims to extend
+  pub fn verify(&self, previous: &Powers<E>) -> Result<bool, CustomError> {
+    let (next, proof) = (&self.powers, &self.proof);
+    previous.check_shape()?;
+    next.check_shape()?;
+    if next.list_g1_tau_i.len() != previous.list_g1_tau_i.len() {
+      return Err(CustomError::MalformedKey);
+    }
+    let g1 = previous.list_g1_tau_i[0];
+    let g2 = previous.list_g2_tau_i[0];
+
+    // the generators stay, s ≠ 0
+    if next.list_g1_tau_i[0] != g1 || next.list_g2_tau_i[0] != g2 || proof.s_g1.is_zero() {
+      return Ok(false);
+    }
+
+    // g^z = r·(g^s)^c
+    let c = previous.challenge(&proof.s_g1, &proof.s_g2, &proof.r)?;
+    let mut rhs = proof.s_g1.mul(c);
+    rhs.add_assign_mixed(&proof.r);
+    if g1.mul(proof.z) != rhs {
+      return Ok(false);
+    }
+
+    // e(g^s, g2) = e(g, g2^s) and e(g^(τ'), g2) = e(g^τ, g2^s)
+    let same_s = E::product_of_pairings(
+      [(proof.s_g1.into(), g2.into()), (g1.neg().into(), proof.s_g2.into())].iter(),
+    );
+    let extends = E::product_of_pairings(
+      [
+        (next.list_g1_tau_i[1].into(), g2.into()),
+        (previous.list_g1_tau_i[1].neg().into(), proof.s_g2.into()),
+      ]
+      .iter(),
+    );
+    if !same_s.is_one() || !extends.is_one() {
+      return Ok(false);
+    }
+
+    next.check()
+  }
+}
+
+// n ≥ 1 must be a domain size
+fn check_size<E: PairingEngine>(n: usize) -> Result<(), CustomError> {
+  let domain = new_domain::<E::Fr>(n).map(|d| d.size());
+  if n == 0 || domain.ok() != Some(n) {
+    return Err(CustomError::UnsupportedDomainSize(n));
+  }
+  Ok(())
+}
+
+impl<E: PairingEngine> Ceremony<E> {
+  pub fn new(n: usize) -> Result<Self, CustomError> {
+    check_size::<E>(n)?;
+    Ok(Ceremony { size: n as u64, contributions: Vec::new() })
+  }
+
+  // n; MalformedKey if it doesn't fit a usize, e.g. on a 32-bit target
+  pub fn size(&self) -> Result<usize, CustomError> {
+    usize::try_from(self.size).map_err(|_| CustomError::MalformedKey)
+  }
+
+  // n, checked against the domain and every contribution before anything is allocated:
+  // a decoded transcript states n itself
+  fn checked_size(&self) -> Result<usize, CustomError> {
+    let n = self.size()?;
+    check_size::<E>(n)?;
+    let shape = |powers: &Powers<E>| powers.list_g1_tau_i.len() == n + 1 && powers.list_g2_tau_i.len() == n + 1;
+    if !self.contributions.iter().all(|contribution| shape(&contribution.powers)) {
+      return Err(CustomError::MalformedKey);
+    }
+    Ok(n)
+  }
+
+  pub fn contributions(&self) -> &[Contribution<E>] {
+    &self.contributions
+  }
+
+  // the powers after the last contribution
+  pub fn current(&self) -> Result<Powers<E>, CustomError> {
+    let n = self.checked_size()?;
+    match self.contributions.last() {
+      Some(contribution) => Ok(contribution.powers.clone()),
+      None => Powers::new(n),
+    }
+  }
+
+  // contribute in process, for a participant that holds the transcript
+  pub fn contribute<R: Rng>(&mut self, rng: &mut R) -> Result<(), CustomError> {
+    let contribution = self.current()?.contribute(rng)?;
+    self.contributions.push(contribution);
+    Ok(())
+  }
+
+  // appends a contribution made elsewhere from current(), after checking it
+  pub fn add(&mut self, contribution: Contribution<E>) -> Result<(), CustomError> {
+    if !contribution.verify(&self.current()?)? {
+      return Err(CustomError::InvalidContribution(self.contributions.len()));
+    }
+    self.contributions.push(contribution);
+    Ok(())
+  }
+
+  // replays every contribution from τ = 1
+  pub fn verify(&self) -> Result<bool, CustomError> {
+    Ok(self.first_invalid()?.is_none())
+  }
+
+  fn first_invalid(&self) -> Result<Option<usize>, CustomError> {
+    let n = self.checked_size()?;
+    if self.contributions.is_empty() {
+      return Ok(None);
+    }
+    let mut previous = Powers::<E>::new(n)?;
+    for (k, contribution) in self.contributions.iter().enumerate() {
+      if !contribution.verify(&previous)? {
+        return Ok(Some(k));
+      }
+      previous = contribution.powers.clone();
+    }
+    Ok(None)
+  }
+
+  // the parameters of the final τ; needs at least one contribution, since τ = 1 is public
+  pub fn into_parameters(self) -> Result<Parameters<E>, CustomError> {
+    if let Some(k) = self.first_invalid()? {
+      return Err(CustomError::InvalidContribution(k));
+    }
+    let powers = match self.contributions.into_iter().last() {
+      Some(contribution) => contribution.powers,
+      None => return Err(CustomError::InvalidContribution(0)),
+    };
+    Parameters::from_powers(powers.list_g1_tau_i, powers.list_g2_tau_i)
+  }
+}
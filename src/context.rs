@@ -0,0 +1,419 @@
+// ASVC context: the keys together with everything derived from n.
+//
+// The free functions take ω and n from the caller and can't tell a wrong one
+// from a wrong opening. Asvc builds the evaluation domain once from the keys,
+// caches ω^0 .. ω^(n-1) when it holds a proving key, and checks every position
+// against n before using it. A verifier only context computes ω^i on demand, so n
+// read from an untrusted verifying key costs no memory.
+use ark_ec::PairingEngine;
+use ark_ff::One;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_std::rand::Rng;
+
+use ark_std::ops::Range;
+use ark_std::vec::Vec;
+
+use crate::bytes::{prove_byte_range_with, verify_byte_range_with};
+use crate::{
+  aggregate_across_commitments, aggregate_across_commitments_with_transcript,
+  aggregate_proofs_with, check_points, commit, commit_bytes, commit_hiding, group_gen,
+  new_domain, prove_all_with, prove_linear_with, prove_pos_hiding_with, prove_pos_with,
+  update_commit_batch_with, update_commit_with, update_proof_with, update_subvector_proof_with,
+  verify_across_commitments_with, verify_all_upks_with, verify_linear_with,
+  verify_pos_hiding_with, verify_pos_with, verify_upk_with, BatchVerifier, ByteRangeProof,
+  Commitment, CustomError, LinearProof, Opening, Parameters, Proof, ProvingKey, Roots,
+  Transcript, UpdateKey, VerifyingKey,
+};
+
+pub struct Asvc<E: PairingEngine> {
+  proving_key: Option<ProvingKey<E>>,
+  verifying_key: VerifyingKey<E>,
+  domain: GeneralEvaluationDomain<E::Fr>,
+  // ω^i, i ∈ [0, n) with a proving key, empty without
+  omega_powers: Vec<E::Fr>,
+}
+
+impl<E: PairingEngine> Asvc<E> {
+  // prover and verifier context; the two keys must describe the same n
+  pub fn new(params: Parameters<E>) -> Result<Self, CustomError> {
+    let Parameters { proving_key, verifying_key } = params;
+    let mut asvc = Self::from_verifying_key(verifying_key)?;
+    if proving_key.list_g1_tau_i.len() != asvc.size() + 1
+      || proving_key.list_l_i.len() != asvc.size()
+      || proving_key.list_update_keys.len() != asvc.size()
+      || proving_key.hiding_key.as_ref().is_some_and(|key| {
+        key.list_h_tau_i.len() != asvc.size() + 1 || key.list_h_l_i.len() != asvc.size()
+      })
+    {
+      return Err(CustomError::MalformedKey);
+    }
+    asvc.proving_key = Some(proving_key);
+
+    let omega = asvc.omega();
+    let mut cur = E::Fr::one();
+    asvc.omega_powers.reserve_exact(asvc.size());
+    for _ in 0..asvc.size() {
+      asvc.omega_powers.push(cur);
+      cur *= &omega;
+    }
+    Ok(asvc)
+  }
+
+  // verifier only context; prover operations fail with MissingProvingKey
+  pub fn from_verifying_key(verifying_key: VerifyingKey<E>) -> Result<Self, CustomError> {
+    // g, g2 and g2^τ at least
+    if verifying_key.list_g1_tau_i.is_empty() || verifying_key.list_g2_tau_i.len() < 2 {
+      return Err(CustomError::MalformedKey);
+    }
+    let n = verifying_key.size()?;
+    let domain: GeneralEvaluationDomain<E::Fr> = new_domain(n)?;
+    if domain.size() != n {
+      return Err(CustomError::UnsupportedDomainSize(n));
+    }
+    // no key has more than the n+1 powers key_gen makes
+    if verifying_key.list_g1_tau_i.len() > n + 1 || verifying_key.list_g2_tau_i.len() > n + 1 {
+      return Err(CustomError::MalformedKey);
+    }
+
+    Ok(Asvc { proving_key: None, verifying_key, domain, omega_powers: Vec::new() })
+  }
+
+  // n
+  pub fn size(&self) -> usize {
+    self.domain.size()
+  }
+
+  pub fn omega(&self) -> E::Fr {
+    group_gen::<E>(&self.domain)
+  }
+
+  pub fn domain(&self) -> &GeneralEvaluationDomain<E::Fr> {
+    &self.domain
+  }
+
+  pub fn proving_key(&self) -> Option<&ProvingKey<E>> {
+    self.proving_key.as_ref()
+  }
+
+  pub fn verifying_key(&self) -> &VerifyingKey<E> {
+    &self.verifying_key
+  }
+
+  // upk_i from the proving key
+  pub fn update_key(&self, point: u32) -> Result<&UpdateKey<E>, CustomError> {
+    check_points(&[point], self.size())?;
+    Ok(&self.prover()?.list_update_keys[point as usize])
+  }
+
+  // upk_i for every position, in order
+  fn update_keys(&self, points: impl Iterator<Item = u32>) -> Result<Vec<UpdateKey<E>>, CustomError> {
+    points.map(|point| self.update_key(point).cloned()).collect()
+  }
+
+  fn check_upk(&self, point: u32, upk: &UpdateKey<E>) -> Result<(), CustomError> {
+    if !self.verify_upk(point, upk)? {
+      return Err(CustomError::InvalidUpdateKey(point));
+    }
+    Ok(())
+  }
+
+  fn prover(&self) -> Result<&ProvingKey<E>, CustomError> {
+    self.proving_key.as_ref().ok_or(CustomError::MissingProvingKey)
+  }
+
+  fn roots(&self) -> Roots<'_, E::Fr> {
+    if self.omega_powers.is_empty() {
+      return Roots::Omega(self.omega());
+    }
+    Roots::Table(&self.omega_powers)
+  }
+
+  pub fn commit(&self, values: Vec<E::Fr>) -> Result<Commitment<E>, CustomError> {
+    commit(self.prover()?, values)
+  }
+
+  pub fn prove_pos(
+    &self,
+    values: Vec<E::Fr>,
+    points: Vec<u32>,
+  ) -> Result<Proof<E>, CustomError> {
+    prove_pos_with(self.prover()?, values, &points, &self.domain, self.roots())
+  }
+
+  // the commitment and its blinding, which prove_pos_hiding needs
+  pub fn commit_hiding<R: Rng>(
+    &self,
+    values: Vec<E::Fr>,
+    rng: &mut R,
+  ) -> Result<(Commitment<E>, Vec<E::Fr>), CustomError> {
+    commit_hiding(self.prover()?, values, rng)
+  }
+
+  pub fn prove_pos_hiding(
+    &self,
+    values: Vec<E::Fr>,
+    blinding: Vec<E::Fr>,
+    points: Vec<u32>,
+  ) -> Result<Proof<E>, CustomError> {
+    prove_pos_hiding_with(self.prover()?, values, blinding, &points, &self.domain, self.roots())
+  }
+
+  pub fn prove_all(&self, values: Vec<E::Fr>) -> Result<Vec<Proof<E>>, CustomError> {
+    prove_all_with(self.prover()?, values, &self.domain)
+  }
+
+  pub fn verify_pos(
+    &self,
+    commit: &Commitment<E>,
+    point_values: Vec<E::Fr>,
+    points: Vec<u32>,
+    proof: &Proof<E>,
+  ) -> Result<bool, CustomError> {
+    verify_pos_with(&self.verifying_key, commit, &point_values, &points, proof, self.roots())
+  }
+
+  pub fn verify_pos_hiding(
+    &self,
+    commit: &Commitment<E>,
+    point_values: Vec<E::Fr>,
+    point_blindings: Vec<E::Fr>,
+    points: Vec<u32>,
+    proof: &Proof<E>,
+  ) -> Result<bool, CustomError> {
+    verify_pos_hiding_with(
+      &self.verifying_key, commit, &point_values, &point_blindings, &points, proof, self.roots(),
+    )
+  }
+
+  pub fn prove_linear(
+    &self,
+    values: Vec<E::Fr>,
+    weights: Vec<E::Fr>,
+  ) -> Result<LinearProof<E>, CustomError> {
+    prove_linear_with(self.prover()?, values, weights, &self.domain)
+  }
+
+  pub fn verify_linear(
+    &self,
+    commit: &Commitment<E>,
+    weights: Vec<E::Fr>,
+    result: E::Fr,
+    proof: &LinearProof<E>,
+  ) -> Result<bool, CustomError> {
+    verify_linear_with(&self.verifying_key, commit, &weights, result, proof, &self.domain)
+  }
+
+  pub fn commit_bytes(&self, data: &[u8]) -> Result<Commitment<E>, CustomError> {
+    commit_bytes(self.prover()?, data)
+  }
+
+  pub fn prove_byte_range(
+    &self,
+    data: &[u8],
+    range: Range<usize>,
+  ) -> Result<ByteRangeProof<E>, CustomError> {
+    prove_byte_range_with(self.prover()?, data, range, &self.domain, self.roots())
+  }
+
+  pub fn verify_byte_range(
+    &self,
+    commit: &Commitment<E>,
+    start: usize,
+    bytes: &[u8],
+    proof: &ByteRangeProof<E>,
+  ) -> Result<bool, CustomError> {
+    verify_byte_range_with(&self.verifying_key, commit, start, bytes, proof, self.roots())
+  }
+
+  pub fn verify_upk(&self, point: u32, upk: &UpdateKey<E>) -> Result<bool, CustomError> {
+    verify_upk_with(&self.verifying_key, point, upk, self.roots())
+  }
+
+  // audits a proving key for this context: every upk_i and l_i at once
+  pub fn verify_all_upks<R: Rng>(
+    &self,
+    prk: &ProvingKey<E>,
+    rng: &mut R,
+  ) -> Result<bool, CustomError> {
+    verify_all_upks_with(&self.verifying_key, prk, self.roots(), rng)
+  }
+
+  // validates the keys of this context, the verifying key alone without a proving key
+  pub fn validate(&self) -> Result<(), CustomError> {
+    self.verifying_key.validate()?;
+    match &self.proving_key {
+      Some(prk) => prk.validate(&self.verifying_key),
+      None => Ok(()),
+    }
+  }
+
+  // c' for v_i += δ, with upk_i from the proving key
+  pub fn update_commit(
+    &self,
+    commit: &Commitment<E>,
+    delta: E::Fr,
+    point: u32,
+  ) -> Result<Commitment<E>, CustomError> {
+    let upk = self.update_key(point)?;
+    update_commit_with(commit, delta, point, upk, self.roots(), self.size())
+  }
+
+  pub fn update_commit_batch(
+    &self,
+    commit: &Commitment<E>,
+    updates: &[(u32, E::Fr)],
+  ) -> Result<Commitment<E>, CustomError> {
+    let upks = self.update_keys(updates.iter().map(|(point, _)| *point))?;
+    update_commit_batch_with(commit, updates, &upks, self.roots(), self.size())
+  }
+
+  // π_i for v_j += δ
+  pub fn update_proof(
+    &self,
+    proof: &Proof<E>,
+    delta: E::Fr,
+    point_i: u32,
+    point_j: u32,
+  ) -> Result<Proof<E>, CustomError> {
+    let (upk_i, upk_j) = (self.update_key(point_i)?, self.update_key(point_j)?);
+    update_proof_with(proof, delta, point_i, point_j, upk_i, upk_j, self.roots(), self.size())
+  }
+
+  // π_I for v_j += δ
+  pub fn update_subvector_proof(
+    &self,
+    proof: &Proof<E>,
+    delta: E::Fr,
+    points: Vec<u32>,
+    point_j: u32,
+  ) -> Result<Proof<E>, CustomError> {
+    check_points(&points, self.size())?;
+    let upks = self.update_keys(points.iter().copied())?;
+    let upk_j = self.update_key(point_j)?;
+    update_subvector_proof_with(proof, delta, &points, point_j, &upks, upk_j, self.roots(), self.size())
+  }
+
+  // Verifier side: the same updates with upk_i sent along, e.g. by the prover.
+  // Each upk_i is checked against its position first; InvalidUpdateKey otherwise.
+  pub fn update_commit_with_upk(
+    &self,
+    commit: &Commitment<E>,
+    delta: E::Fr,
+    point: u32,
+    upk: &UpdateKey<E>,
+  ) -> Result<Commitment<E>, CustomError> {
+    self.check_upk(point, upk)?;
+    update_commit_with(commit, delta, point, upk, self.roots(), self.size())
+  }
+
+  pub fn update_commit_batch_with_upks(
+    &self,
+    commit: &Commitment<E>,
+    updates: &[(u32, E::Fr)],
+    upks: &[UpdateKey<E>],
+  ) -> Result<Commitment<E>, CustomError> {
+    if updates.len() != upks.len() {
+      return Err(CustomError::LengthMismatch { expected: updates.len(), found: upks.len() });
+    }
+    for ((point, _), upk) in updates.iter().zip(upks.iter()) {
+      self.check_upk(*point, upk)?;
+    }
+    update_commit_batch_with(commit, updates, upks, self.roots(), self.size())
+  }
+
+  pub fn update_proof_with_upks(
+    &self,
+    proof: &Proof<E>,
+    delta: E::Fr,
+    point_i: u32,
+    point_j: u32,
+    upk_i: &UpdateKey<E>,
+    upk_j: &UpdateKey<E>,
+  ) -> Result<Proof<E>, CustomError> {
+    self.check_upk(point_i, upk_i)?;
+    self.check_upk(point_j, upk_j)?;
+    update_proof_with(proof, delta, point_i, point_j, upk_i, upk_j, self.roots(), self.size())
+  }
+
+  pub fn update_subvector_proof_with_upks(
+    &self,
+    proof: &Proof<E>,
+    delta: E::Fr,
+    points: Vec<u32>,
+    point_j: u32,
+    upks: &[UpdateKey<E>],
+    upk_j: &UpdateKey<E>,
+  ) -> Result<Proof<E>, CustomError> {
+    check_points(&points, self.size())?;
+    if points.len() != upks.len() {
+      return Err(CustomError::LengthMismatch { expected: points.len(), found: upks.len() });
+    }
+    for (point, upk) in points.iter().zip(upks.iter()) {
+      self.check_upk(*point, upk)?;
+    }
+    self.check_upk(point_j, upk_j)?;
+    update_subvector_proof_with(proof, delta, &points, point_j, upks, upk_j, self.roots(), self.size())
+  }
+
+  pub fn aggregate_proofs(
+    &self,
+    points: Vec<u32>,
+    proofs: Vec<Proof<E>>,
+  ) -> Result<Proof<E>, CustomError> {
+    check_points(&points, self.size())?;
+    aggregate_proofs_with(&points, &proofs, self.roots())
+  }
+
+  pub fn aggregate_across_commitments(
+    &self,
+    openings: &[Opening<E>],
+    proofs: Vec<Proof<E>>,
+  ) -> Result<Proof<E>, CustomError> {
+    aggregate_across_commitments(openings, proofs)
+  }
+
+  pub fn verify_across_commitments(
+    &self,
+    openings: &[Opening<E>],
+    proof: &Proof<E>,
+  ) -> Result<bool, CustomError> {
+    verify_across_commitments_with(&self.verifying_key, openings, proof, self.roots(), None)
+  }
+
+  pub fn aggregate_across_commitments_with_transcript(
+    &self,
+    openings: &[Opening<E>],
+    proofs: Vec<Proof<E>>,
+    transcript: &mut Transcript,
+  ) -> Result<Proof<E>, CustomError> {
+    aggregate_across_commitments_with_transcript(openings, proofs, transcript)
+  }
+
+  pub fn verify_across_commitments_with_transcript(
+    &self,
+    openings: &[Opening<E>],
+    proof: &Proof<E>,
+    transcript: &mut Transcript,
+  ) -> Result<bool, CustomError> {
+    let roots = self.roots();
+    verify_across_commitments_with(&self.verifying_key, openings, proof, roots, Some(transcript))
+  }
+
+  // same as BatchVerifier::verify
+  pub fn verify_batch<R: Rng>(
+    &self,
+    batch: &BatchVerifier<E>,
+    rng: &mut R,
+  ) -> Result<Vec<usize>, CustomError> {
+    batch.verify_with_roots(&self.verifying_key, self.roots(), rng)
+  }
+
+  // same as BatchVerifier::verify_with_transcript
+  pub fn verify_batch_with_transcript(
+    &self,
+    batch: &BatchVerifier<E>,
+    transcript: &mut Transcript,
+  ) -> Result<Vec<usize>, CustomError> {
+    batch.verify_with_roots_transcript(&self.verifying_key, self.roots(), transcript)
+  }
+}
@@ -0,0 +1,205 @@
+// Byte encoding for keys, commitments and proofs.
+//
+// Every encoding starts with a 3 byte header [FORMAT_VERSION, kind, compression]
+// followed by the ark-serialize body of the value.
+// Decoding is always checked: every group element must lie on the curve
+// and in the prime order subgroup, and no trailing bytes are accepted.
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_std::{vec, vec::Vec};
+
+use crate::{
+  ByteRangeProof, Ceremony, Commitment, Contribution, CustomError, LinearProof, Parameters, Proof, ProvingKey,
+  UpdateKey, VerifyingKey,
+};
+
+// 2: VerifyingKey carries n
+// 3: optional hiding key in ProvingKey and VerifyingKey
+pub const FORMAT_VERSION: u8 = 3;
+
+const HEADER_LEN: usize = 3;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compression {
+  Compressed,
+  Uncompressed,
+}
+
+impl Compression {
+  fn to_byte(self) -> u8 {
+    match self {
+      Compression::Uncompressed => 0,
+      Compression::Compressed => 1,
+    }
+  }
+
+  fn from_byte(b: u8) -> Option<Self> {
+    match b {
+      0 => Some(Compression::Uncompressed),
+      1 => Some(Compression::Compressed),
+      _ => None,
+    }
+  }
+}
+
+pub trait Encode: CanonicalSerialize + CanonicalDeserialize {
+  // type tag stored in the header, so a proof can't be read back as a commitment
+  const KIND: u8;
+
+  // on-curve and subgroup check of every group element in the value
+  fn check(&self) -> Result<(), SerializationError>;
+
+  fn to_bytes(&self, compression: Compression) -> Result<Vec<u8>, CustomError> {
+    let mut bytes = vec![FORMAT_VERSION, Self::KIND, compression.to_byte()];
+    match compression {
+      Compression::Compressed => self.serialize(&mut bytes)?,
+      Compression::Uncompressed => self.serialize_uncompressed(&mut bytes)?,
+    }
+    Ok(bytes)
+  }
+
+  fn from_bytes(bytes: &[u8]) -> Result<Self, CustomError> {
+    if bytes.len() < HEADER_LEN {
+      return Err(CustomError::InvalidHeader);
+    }
+    if bytes[0] != FORMAT_VERSION {
+      return Err(CustomError::UnsupportedFormatVersion(bytes[0]));
+    }
+    if bytes[1] != Self::KIND {
+      return Err(CustomError::InvalidHeader);
+    }
+    let compression = Compression::from_byte(bytes[2]).ok_or(CustomError::InvalidHeader)?;
+
+    let mut reader = &bytes[HEADER_LEN..];
+    let value = match compression {
+      // compressed points are rebuilt from x, which already checks curve and subgroup
+      Compression::Compressed => Self::deserialize(&mut reader)?,
+      Compression::Uncompressed => {
+        let value = Self::deserialize_unchecked(&mut reader)?;
+        value.check()?;
+        value
+      }
+    };
+    if !reader.is_empty() {
+      return Err(SerializationError::InvalidData.into());
+    }
+    Ok(value)
+  }
+}
+
+// ark-ec only runs the subgroup check on uncompressed points, not the curve equation.
+// Going through the compressed form recomputes y from x, so a point off the curve
+// either fails to decode or comes back different.
+fn check_point<G: AffineCurve>(point: &G) -> Result<(), SerializationError> {
+  let mut bytes = Vec::with_capacity(point.serialized_size());
+  point.serialize(&mut bytes)?;
+  if G::deserialize(&bytes[..])? != *point {
+    return Err(SerializationError::InvalidData);
+  }
+  Ok(())
+}
+
+fn check_points<G: AffineCurve>(points: &[G]) -> Result<(), SerializationError> {
+  points.iter().try_for_each(check_point)
+}
+
+impl<E: PairingEngine> Encode for UpdateKey<E> {
+  const KIND: u8 = 1;
+
+  fn check(&self) -> Result<(), SerializationError> {
+    check_point(&self.a_i)?;
+    check_point(&self.u_i)
+  }
+}
+
+impl<E: PairingEngine> Encode for ProvingKey<E> {
+  const KIND: u8 = 2;
+
+  fn check(&self) -> Result<(), SerializationError> {
+    check_points(&self.list_g1_tau_i)?;
+    check_points(&self.list_l_i)?;
+    self.list_update_keys.iter().try_for_each(|upk| upk.check())?;
+    if let Some(key) = &self.hiding_key {
+      check_points(&key.list_h_tau_i)?;
+      check_points(&key.list_h_l_i)?;
+    }
+    Ok(())
+  }
+}
+
+impl<E: PairingEngine> Encode for VerifyingKey<E> {
+  const KIND: u8 = 3;
+
+  fn check(&self) -> Result<(), SerializationError> {
+    check_points(&self.list_g1_tau_i)?;
+    check_points(&self.list_g2_tau_i)?;
+    check_point(&self.a)?;
+    if let Some(list_h_tau_i) = &self.list_h_tau_i {
+      check_points(list_h_tau_i)?;
+    }
+    Ok(())
+  }
+}
+
+impl<E: PairingEngine> Encode for Parameters<E> {
+  const KIND: u8 = 4;
+
+  fn check(&self) -> Result<(), SerializationError> {
+    self.proving_key.check()?;
+    self.verifying_key.check()
+  }
+}
+
+impl<E: PairingEngine> Encode for Commitment<E> {
+  const KIND: u8 = 5;
+
+  fn check(&self) -> Result<(), SerializationError> {
+    check_point(&self.commit)
+  }
+}
+
+impl<E: PairingEngine> Encode for Proof<E> {
+  const KIND: u8 = 6;
+
+  fn check(&self) -> Result<(), SerializationError> {
+    check_point(&self.w)
+  }
+}
+
+impl<E: PairingEngine> Encode for Contribution<E> {
+  const KIND: u8 = 7;
+
+  fn check(&self) -> Result<(), SerializationError> {
+    check_points(&self.powers.list_g1_tau_i)?;
+    check_points(&self.powers.list_g2_tau_i)?;
+    check_point(&self.proof.s_g1)?;
+    check_point(&self.proof.s_g2)?;
+    check_point(&self.proof.r)
+  }
+}
+
+impl<E: PairingEngine> Encode for Ceremony<E> {
+  const KIND: u8 = 8;
+
+  fn check(&self) -> Result<(), SerializationError> {
+    self.contributions().iter().try_for_each(|contribution| contribution.check())
+  }
+}
+
+impl<E: PairingEngine> Encode for LinearProof<E> {
+  const KIND: u8 = 9;
+
+  fn check(&self) -> Result<(), SerializationError> {
+    check_point(&self.q)?;
+    check_point(&self.r)?;
+    check_point(&self.r_shifted)
+  }
+}
+
+impl<E: PairingEngine> Encode for ByteRangeProof<E> {
+  const KIND: u8 = 10;
+
+  fn check(&self) -> Result<(), SerializationError> {
+    check_point(&self.proof.w)
+  }
+}
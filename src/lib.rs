@@ -1,31 +1,143 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use std::io::Error; use std::ops::{AddAssign, SubAssign};
-use std::u32;
+use ark_std::io::Error; use ark_std::ops::{AddAssign, SubAssign};
 // temp. r1cs::SynthesisError
-use std::{ops::{Div, MulAssign, Add}, usize};
+use ark_std::ops::{MulAssign, Add};
+use ark_std::convert::TryFrom;
+use ark_std::fmt;
+use ark_std::collections::BTreeSet;
+use ark_std::{vec, vec::Vec};
 
 use ark_ec::msm::VariableBaseMSM;
 use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve, msm::FixedBaseMSM};
-use ark_ff::{Field, One, Zero, PrimeField, UniformRand};
-use ark_poly::univariate::{DenseOrSparsePolynomial, DensePolynomial};
-
+use ark_ff::{batch_inversion, FftField, Field, One, Zero, PrimeField, UniformRand};
+use ark_poly::univariate::DensePolynomial;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+
+use ark_std::cfg_iter;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use blake2::{Blake2b, Digest};
 use ark_std::rand::Rng;   // in ver3.0 of ark_ec, use ark_std instead of rand::Rng
-use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, Polynomial, UVPolynomial};
-
-use std::ops::{Sub, Mul, Neg};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, UVPolynomial};
+
+use ark_std::ops::{Sub, Mul, Neg};
+
+mod batch;
+mod bytes;
+mod ceremony;
+mod context;
+mod encoding;
+#[cfg(feature = "std")]
+mod ptau;
+mod store;
+mod subproduct_tree;
+mod transcript;
+mod validate;
+pub use batch::BatchVerifier;
+pub use bytes::{
+  bytes_per_element, commit_bytes, pack, prove_byte_range, unpack, verify_byte_range,
+  ByteRangeProof,
+};
+pub use ceremony::{Ceremony, Contribution, ContributionProof, Powers};
+pub use context::Asvc;
+pub use encoding::{Compression, Encode, FORMAT_VERSION};
+#[cfg(feature = "std")]
+pub use ptau::{load_ptau, read_ptau};
+pub use store::VectorStore;
+pub use transcript::Transcript;
+pub use validate::ParameterCheck;
+
+use subproduct_tree::SubproductTree;
 
 #[derive(Debug)]
 pub enum CustomError {
   UnexpectedError,
   IoError(Error),
+  SerializationError(SerializationError),
+  // header names a format version this build cannot read
+  UnsupportedFormatVersion(u8),
+  // header names a different type or compression mode than requested
+  InvalidHeader,
+  // position i is not in [0, n)
+  IndexOutOfRange { index: u32, size: usize },
+  // position i appears twice in an index set
+  DuplicateIndex(u32),
+  // two inputs that must be paired up (points/values, points/proofs) differ in length
+  LengthMismatch { expected: usize, found: usize },
+  // no evaluation domain of this size exists over E::Fr
+  UnsupportedDomainSize(usize),
+  // |I| exceeds what the verifying key powers can check
+  SubvectorTooLarge { size: usize, max: usize },
+  // more values than the proving key has l_i
+  VectorTooLong { size: usize, max: usize },
+  EmptyVector,
+  EmptyIndexSet,
+  // a key is missing the powers an operation needs
+  MalformedKey,
+  // a prover operation on a context built from a verifying key only
+  MissingProvingKey,
+  // a setup ceremony contribution does not extend the powers it claims to
+  InvalidContribution(usize),
+  // g^(τ^i), g2^(τ^i) are not powers of one τ
+  InvalidPowers,
+  // a hiding operation with keys generated without h
+  MissingHidingKey,
+  // an empty byte range, or one past the end of the blob
+  InvalidByteRange { start: usize, end: usize, len: usize },
+  // an update key that does not verify for its position
+  InvalidUpdateKey(u32),
+  // received parameters fail one of the checks of validate
+  InvalidParameters(ParameterCheck),
 }
 
+impl fmt::Display for CustomError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      CustomError::UnexpectedError => write!(f, "unexpected error"),
+      CustomError::IoError(e) => write!(f, "I/O error: {}", e),
+      CustomError::SerializationError(e) => write!(f, "serialization error: {}", e),
+      CustomError::UnsupportedFormatVersion(v) => write!(f, "unsupported format version {}", v),
+      CustomError::InvalidHeader => write!(f, "invalid encoding header"),
+      CustomError::IndexOutOfRange { index, size } =>
+        write!(f, "index {} out of range for vector of size {}", index, size),
+      CustomError::DuplicateIndex(i) => write!(f, "index {} appears more than once", i),
+      CustomError::LengthMismatch { expected, found } =>
+        write!(f, "length mismatch: expected {}, found {}", expected, found),
+      CustomError::UnsupportedDomainSize(n) => write!(f, "no evaluation domain of size {}", n),
+      CustomError::SubvectorTooLarge { size, max } =>
+        write!(f, "subvector of size {} exceeds the verifying key limit {}", size, max),
+      CustomError::VectorTooLong { size, max } =>
+        write!(f, "vector of size {} exceeds the proving key size {}", size, max),
+      CustomError::EmptyVector => write!(f, "empty vector"),
+      CustomError::EmptyIndexSet => write!(f, "empty index set"),
+      CustomError::MalformedKey => write!(f, "malformed key"),
+      CustomError::MissingProvingKey => write!(f, "no proving key in this context"),
+      CustomError::InvalidContribution(k) => write!(f, "invalid ceremony contribution {}", k),
+      CustomError::InvalidPowers => write!(f, "inconsistent powers of tau"),
+      CustomError::MissingHidingKey => write!(f, "no hiding key in these parameters"),
+      CustomError::InvalidByteRange { start, end, len } =>
+        write!(f, "byte range {}..{} is empty or outside a blob of {} bytes", start, end, len),
+      CustomError::InvalidUpdateKey(i) => write!(f, "invalid update key for position {}", i),
+      CustomError::InvalidParameters(check) => write!(f, "invalid parameters: {}", check),
+    }
+  }
+}
+
+impl ark_std::error::Error for CustomError {}
+
 impl From<Error> for CustomError {
   fn from(e: Error) -> CustomError {
     CustomError::IoError(e)
   }
 }
 
+impl From<SerializationError> for CustomError {
+  fn from(e: SerializationError) -> CustomError {
+    CustomError::SerializationError(e)
+  }
+}
+
 // Module explanation
 // - ark_poly::GeneralEvaluationDomain
 //    : Defines a domain over which finite field (I)FFTs can be performed. 
@@ -37,60 +149,195 @@ impl From<Error> for CustomError {
 //    For efficiency, we recommend that the field has at least one large subgroup generated by a root of unity.
 // - ark_ec::msm::FixedBaseMSM
 //    : MSM - Multi Scalar Multiplication
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct UpdateKey<E: PairingEngine>{
   pub a_i: E::G1Affine,
   pub u_i: E::G1Affine,
 }
 
-#[derive(Clone, Eq, PartialEq)]
+// powers of a second generator h with unknown log_g(h), for hiding commitments
+#[derive(Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HidingKey<E: PairingEngine> {
+  pub list_h_tau_i: Vec<E::G1Affine>,  // h^(tau^i), vector-length: n+1
+  pub list_h_l_i: Vec<E::G1Affine>,    // h^(L_i(tau))
+}
+
+#[derive(Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct ProvingKey<E: PairingEngine> {
   pub list_g1_tau_i: Vec<E::G1Affine>,  // g^(tau^i), vector-length:n
   pub list_l_i: Vec<E::G1Affine>,        // l_i
   pub list_update_keys: Vec<UpdateKey<E>>,
+  pub hiding_key: Option<HidingKey<E>>,
 }
 
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct VerifyingKey<E: PairingEngine> {
   pub list_g1_tau_i: Vec<E::G1Affine>,  // g^(tau^i), vector-length: |I|
   pub list_g2_tau_i: Vec<E::G2Affine>,
   pub a: E::G1Affine,              // a
+  pub n: u64,                      // vector length, the domain size
+  pub list_h_tau_i: Option<Vec<E::G1Affine>>,  // h^(tau^i), vector-length: |I|
 }
 
-#[derive(Clone)]
+impl<E: PairingEngine> VerifyingKey<E> {
+  // n as a usize; MalformedKey if it doesn't fit, e.g. on a 32-bit target
+  pub(crate) fn size(&self) -> Result<usize, CustomError> {
+    usize::try_from(self.n).map_err(|_| CustomError::MalformedKey)
+  }
+}
+
+#[derive(Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Parameters<E: PairingEngine> {
   pub proving_key: ProvingKey<E>,
   pub verifying_key: VerifyingKey<E>,
 }
 
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Commitment<E: PairingEngine> {
   pub commit: E::G1Affine,
 }
 
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Proof<E: PairingEngine> {
   pub w: E::G1Affine,
 }
 
-fn group_gen<E: PairingEngine> (domain: &GeneralEvaluationDomain<E::Fr>) -> E::Fr {
+// ∑ w_i·v_i = y for public weights w, by a univariate sumcheck over the domain:
+// with φ, W interpolating v, w and ∑_(ω^i) f(ω^i) = n·f_0 for deg f < n,
+//   φ(x)W(x) = q(x)(x^n - 1) + x·r(x) + y/n,  deg r ≤ n-2
+// r_shifted = g^(τ^2·r(τ)) bounds the degree of r by the n+1 powers of the key.
+#[derive(Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LinearProof<E: PairingEngine> {
+  pub q: E::G1Affine,
+  pub r: E::G1Affine,
+  pub r_shifted: E::G1Affine,
+}
+
+// (c, I, v_I): a claimed opening of commitment c at the positions I
+pub type Opening<E> = (Commitment<E>, Vec<u32>, Vec<<E as PairingEngine>::Fr>);
+
+pub(crate) fn group_gen<E: PairingEngine> (domain: &GeneralEvaluationDomain<E::Fr>) -> E::Fr {
   match domain {
     GeneralEvaluationDomain::Radix2(radix) => radix.group_gen,
     GeneralEvaluationDomain::MixedRadix(mixed ) => mixed.group_gen,
   }
 }
 
+// I ⊆ [0, n), non-empty, no repeated positions
+pub(crate) fn check_points(points: &[u32], n: usize) -> Result<(), CustomError> {
+  if points.is_empty() {
+    return Err(CustomError::EmptyIndexSet);
+  }
+  for point in points.iter() {
+    if *point as usize >= n {
+      return Err(CustomError::IndexOutOfRange { index: *point, size: n });
+    }
+  }
+  check_distinct(points)
+}
+
+fn check_distinct(points: &[u32]) -> Result<(), CustomError> {
+  let mut seen = BTreeSet::new();
+  for point in points.iter() {
+    if !seen.insert(*point) {
+      return Err(CustomError::DuplicateIndex(*point));
+    }
+  }
+  Ok(())
+}
+
+fn check_len(expected: usize, found: usize) -> Result<(), CustomError> {
+  if expected != found {
+    return Err(CustomError::LengthMismatch { expected, found });
+  }
+  Ok(())
+}
+
+// ω^i, either computed from ω or read from a table of ω^0 .. ω^(n-1)
+// (a table lookup assumes i has already been checked against n)
+#[derive(Clone, Copy)]
+pub(crate) enum Roots<'a, F: Field> {
+  Omega(F),
+  Table(&'a [F]),
+}
+
+impl<'a, F: Field> Roots<'a, F> {
+  pub(crate) fn at(&self, i: u32) -> F {
+    match self {
+      Roots::Omega(omega) => omega.pow([i as u64]),
+      Roots::Table(powers) => powers[i as usize],
+    }
+  }
+}
+
+// subproduct tree over ω^i, i ∈ I
+fn points_tree<F: FftField>(points: &[u32], roots: Roots<F>) -> SubproductTree<F> {
+  let roots: Vec<F> = points.iter().map(|point| roots.at(*point)).collect();
+  SubproductTree::new(&roots)
+}
+
+// positions are u32, so no vector is longer than 2^32
+const MAX_SIZE: u64 = 1 << 32;
+
+// the smallest domain of size ≥ n over F; how large a domain F has depends on the curve
+pub(crate) fn new_domain<F: FftField>(n: usize) -> Result<GeneralEvaluationDomain<F>, CustomError> {
+  // before rounding n up, which overflows near usize::MAX
+  if n as u64 > MAX_SIZE {
+    return Err(CustomError::UnsupportedDomainSize(n));
+  }
+  match GeneralEvaluationDomain::<F>::new(n) {
+    Some(domain) if domain.size() as u64 <= MAX_SIZE => Ok(domain),
+    _ => Err(CustomError::UnsupportedDomainSize(n)),
+  }
+}
+
+// 1/n as a field element, n = |domain|
+fn n_inverse<F: Field>(n: usize) -> Result<F, CustomError> {
+  F::from(n as u64).inverse().ok_or(CustomError::UnsupportedDomainSize(n))
+}
+
 pub fn key_gen<E: PairingEngine, R: Rng> (n: usize, rng: &mut R) -> Result<Parameters<E>, CustomError> {
+  key_gen_with_progress(n, rng, |_, _| {})
+}
+
+// key_gen with a hiding key, for commit_hiding
+pub fn key_gen_hiding<E: PairingEngine, R: Rng> (n: usize, rng: &mut R) -> Result<Parameters<E>, CustomError> {
+  generate(n, true, rng, |_, _| {})
+}
+
+// positions handled per batch in key_gen: bounds the scalars and projective points
+// held at once, and how often progress is reported
+const KEY_GEN_CHUNK: usize = 1 << 14;
+
+// key_gen, calling progress(done, n) after every batch of positions
+pub fn key_gen_with_progress<E: PairingEngine, R: Rng, P: FnMut(usize, usize)> (
+  n: usize,
+  rng: &mut R,
+  progress: P,
+) -> Result<Parameters<E>, CustomError> {
+  generate(n, false, rng, progress)
+}
+
+fn generate<E: PairingEngine, R: Rng, P: FnMut(usize, usize)> (
+  n: usize,
+  hiding: bool,
+  rng: &mut R,
+  mut progress: P,
+) -> Result<Parameters<E>, CustomError> {
   // rand from ark_ff::{UniformRand}
     // type Fr: PrimeField + SquareRootField - This is the scalar field of the G1/G2 groups.
+  if n == 0 {
+    return Err(CustomError::UnsupportedDomainSize(n));
+  }
   let tau = E::Fr::rand(rng);
   let g1 = E::G1Projective::rand(rng);
   let g2 = E::G2Projective::rand(rng);
+  let h = if hiding { Some(E::G1Projective::rand(rng)) } else { None };
 
   // Evaluation Domain - Subgroup!! <Not Fully Understood..>
-  let domain: GeneralEvaluationDomain<E::Fr> = 
-    EvaluationDomain::<E::Fr>::new(n).unwrap();//.ok_or(Error(0))?;
+  let domain: GeneralEvaluationDomain<E::Fr> = new_domain(n)?;
   let max_degree = domain.size();
+  let n_inv = n_inverse::<E::Fr>(max_degree)?;
 
   let scalar_bit = E::Fr::size_in_bits(); // size_in_bits from ark_ff::PrimeField
   let g1_window = FixedBaseMSM::get_mul_window_size(max_degree+1);
@@ -109,89 +356,275 @@ pub fn key_gen<E: PairingEngine, R: Rng> (n: usize, rng: &mut R) -> Result<Param
 
   // n-SDH public parameters : g, g^τ , g^(τ^2), ... g^(τ^n)
 
-  let mut list_g1_tau_i = 
+  let list_g1_tau_i = 
     FixedBaseMSM::multi_scalar_mul(scalar_bit, g1_window, &g1_table, &curs);
   // batch_normalization_into_affine from ark_ec::ProjectiveCurve trait
     // Normalizes a slice of projective elements and outputs a vector containing the affine equivalents.
-  let list_g1_tau_i = E::G1Projective::batch_normalization_into_affine(&mut list_g1_tau_i);
+  let list_g1_tau_i = E::G1Projective::batch_normalization_into_affine(&list_g1_tau_i);
 
-  let mut list_g2_tau_i =
+  let list_g2_tau_i =
     FixedBaseMSM::multi_scalar_mul(scalar_bit, g2_window, &g2_table, &curs);
-  let list_g2_tau_i = E::G2Projective::batch_normalization_into_affine(&mut list_g2_tau_i);
+  let list_g2_tau_i = E::G2Projective::batch_normalization_into_affine(&list_g2_tau_i);
   
-  // a = g^A(τ) when A(τ) = τ^n - 1 .. (why sub g1, not 1 ??)
+  // a = g^A(τ) when A(τ) = τ^n - 1
+  let a_tau = curs[max_degree] - E::Fr::one();
   let a = list_g1_tau_i[max_degree].into_projective().sub(&g1); // sub from core::ops::{Sub} trait
 
-  let mut update_keys : Vec<UpdateKey<E>> = Vec::new();
-  let mut l_of_g1 : Vec<E::G1Projective> = Vec::new();
-
-  for i in 0..max_degree {
-    // 1/(τ-ω^i)
-    let tau_omega_i_divisor =
-        E::Fr::one().div(&tau.sub(&group_gen::<E>(&domain).pow(&[i as u64])));
-    /*
-    let omega = group_gen::<E>(&domain);
-    let omega_i = omega.pow(&[i as u64]);
-    let tau_omega_i = tau.sub(&omega_i);
-    let tau_omega_i_divisor = E::Fr::one().div(&tau_omega_i);
-    */
-
-    // ai = g_1^(A(τ)/(τ-ω^i))  // <E as PairingEngine>::G1Projective
-    let a_i = a.mul(tau_omega_i_divisor.into_repr());   
-    // <- tau_omega_i_divisor.into occurs an compile error.
-
-    // 1/nω^(n-i) = ω^i/n
-    let a_aside_omega_i_divisor = group_gen::<E>(&domain)
-      .pow(&[i as u64]) // limbs - list of integers
-      .div(&E::Fr::from_repr((max_degree as u64).into()).unwrap());
-    // from_repr : Returns a prime field element from its underlying representation.
-
-    // li = g_1^L_i(x) = g_1^(A(τ)/((x-ω^i)*A'(ω^i))) = ai^(1/A'(ω^i))
-    let l_i = a_i.mul(a_aside_omega_i_divisor.into_repr());  // scalar multiplication
-    // <- a_aside_omega_i_divisor.into occurs an compile error
-
-    // ui = (li-1)/(x-ω^i)
-    let mut u_i = l_i.sub(&g1);
-    u_i = u_i.mul(tau_omega_i_divisor.into_repr());  // compile error with into
-
-    // batch_normalization_into_affine?
-    let upk = UpdateKey {
-      a_i: a_i.into_affine(),
-      u_i: u_i.into_affine(),
+  // h^(τ^i) and h^(L_i(τ)) by the same IFFT as lagrange_keys
+  let hiding_key = match h {
+    Some(h) => {
+      let h_table = FixedBaseMSM::get_window_table::<E::G1Projective>(scalar_bit, g1_window, h);
+      let list_h_tau_i = FixedBaseMSM::multi_scalar_mul(scalar_bit, g1_window, &h_table, &curs);
+      let list_h_tau_i = E::G1Projective::batch_normalization_into_affine(&list_h_tau_i);
+      let list_h_l_i = lagrange_keys::<E>(&list_h_tau_i)?;
+      Some(HidingKey::<E> { list_h_tau_i, list_h_l_i })
+    }
+    None => None,
+  };
+  drop(curs);
+
+  let omega = group_gen::<E>(&domain);
+  let mut omega_i = E::Fr::one();
+  let mut update_keys : Vec<UpdateKey<E>> = Vec::with_capacity(max_degree);
+  let mut list_l_i : Vec<E::G1Affine> = Vec::with_capacity(max_degree);
+
+  for start in (0..max_degree).step_by(KEY_GEN_CHUNK) {
+    let len = KEY_GEN_CHUNK.min(max_degree - start);
+
+    // ω^i, one multiplication each
+    let mut omega_powers = Vec::with_capacity(len);
+    for _ in 0..len {
+      omega_powers.push(omega_i);
+      omega_i *= &omega;
+    }
+
+    // 1/(τ-ω^i), one inversion for the whole batch
+    // τ = ω^i happens with negligible probability, but must not abort
+    let mut divisors: Vec<E::Fr> = cfg_iter!(omega_powers).map(|omega_i| tau - omega_i).collect();
+    if divisors.iter().any(|d| d.is_zero()) {
+      return Err(CustomError::UnexpectedError);
+    }
+    batch_inversion(&mut divisors);
+
+    // ai = g_1^(A(τ)/(τ-ω^i))
+    let a_scalars: Vec<E::Fr> = cfg_iter!(divisors).map(|d| a_tau * d).collect();
+    // li = g_1^L_i(τ) = ai^(1/A'(ω^i)) = ai^(ω^i/n)
+    let l_scalars: Vec<E::Fr> = cfg_iter!(a_scalars)
+      .zip(&omega_powers)
+      .map(|(a_i, omega_i)| *a_i * omega_i * n_inv)
+      .collect();
+    // ui = g_1^((L_i(τ)-1)/(τ-ω^i))
+    let u_scalars: Vec<E::Fr> = cfg_iter!(l_scalars)
+      .zip(&divisors)
+      .map(|(l_i, d)| (*l_i - E::Fr::one()) * d)
+      .collect();
+
+    let mul = |scalars: &[E::Fr]| {
+      let points = FixedBaseMSM::multi_scalar_mul::<E::G1Projective>(scalar_bit, g1_window, &g1_table, scalars);
+      E::G1Projective::batch_normalization_into_affine(&points)
     };
+    let list_a_i = mul(&a_scalars);
+    let list_u_i = mul(&u_scalars);
+    list_l_i.extend(mul(&l_scalars));
+    update_keys.extend(list_a_i.into_iter().zip(list_u_i).map(|(a_i, u_i)| UpdateKey { a_i, u_i }));
 
-    update_keys.push(upk);
-    l_of_g1.push(l_i);
+    progress(start + len, max_degree);
   }
-  let l_of_g1 = E::G1Projective::batch_normalization_into_affine(&mut l_of_g1);
 
   let params = Parameters::<E> {
-    proving_key: ProvingKey::<E> {
-      list_g1_tau_i: list_g1_tau_i.clone(),
-      list_l_i: l_of_g1,
-      list_update_keys: update_keys,
-    },
     verifying_key: VerifyingKey::<E> {
-      list_g1_tau_i: list_g1_tau_i,
-      list_g2_tau_i: list_g2_tau_i,
+      list_g1_tau_i: list_g1_tau_i.clone(),
+      list_g2_tau_i,
       a: a.into_affine(),
+      n: max_degree as u64,
+      list_h_tau_i: hiding_key.as_ref().map(|key| key.list_h_tau_i.clone()),
+    },
+    proving_key: ProvingKey::<E> {
+      list_g1_tau_i,
+      list_l_i,
+      list_update_keys: update_keys,
+      hiding_key,
     },
   };
   Ok(params)
   
 }
 
+// n and the domain of size n for g^(τ^i), i ∈ [0, n]
+fn powers_domain<E: PairingEngine>(
+  list_g1_tau_i: &[E::G1Affine],
+) -> Result<(usize, GeneralEvaluationDomain<E::Fr>), CustomError> {
+  if list_g1_tau_i.len() < 2 {
+    return Err(CustomError::MalformedKey);
+  }
+  let n = list_g1_tau_i.len() - 1;
+  let domain: GeneralEvaluationDomain<E::Fr> = new_domain(n)?;
+  if domain.size() != n {
+    return Err(CustomError::UnsupportedDomainSize(n));
+  }
+  Ok((n, domain))
+}
+
+// l_i = g^(L_i(τ)) from g^(τ^i), i ∈ [0, n] alone.
+// L_i(x) = 1/n ∑_j ω^(-ij) x^j, so (l_0, .., l_(n-1)) is the inverse DFT of
+// (g, g^τ, .., g^(τ^(n-1))) over the domain, an O(n log n) IFFT in G1.
+pub fn lagrange_keys<E: PairingEngine>(
+  list_g1_tau_i: &[E::G1Affine],
+) -> Result<Vec<E::G1Affine>, CustomError> {
+  let (n, domain) = powers_domain::<E>(list_g1_tau_i)?;
+  let mut list_l_i: Vec<E::G1Projective> =
+    list_g1_tau_i[..n].iter().map(|g| g.into_projective()).collect();
+  domain.ifft_in_place(&mut list_l_i);
+  Ok(E::G1Projective::batch_normalization_into_affine(&list_l_i))
+}
+
+// upk_i = (a_i, u_i) from g^(τ^i), i ∈ [0, n] alone, with two FFTs in G1.
+// Writing p_j = g^(τ^j), both keys are sums ∑_d ω^(-id) y_d, i.e. entry -i mod n of DFT(y):
+// - A(x)/(x-ω^i) = ∑_(d=1..n) ω^(-id) x^(d-1), so a_i takes y_d = p_(d-1) (y_0 = p_(n-1))
+// - (L_i(x)-1)/(x-ω^i) = ∑_(d=1..n-1) (n-d)/n · ω^(-id) x^(d-1), the Toeplitz sum of
+//   (z^j - 1)/(z - 1) over j with z = x/ω^i, so u_i takes y_d = (n-d)/n · p_(d-1), y_0 = 0
+pub fn update_keys<E: PairingEngine>(
+  list_g1_tau_i: &[E::G1Affine],
+) -> Result<Vec<UpdateKey<E>>, CustomError> {
+  let (n, domain) = powers_domain::<E>(list_g1_tau_i)?;
+  let n_inv = n_inverse::<E::Fr>(n)?;
+
+  let mut a: Vec<E::G1Projective> = Vec::with_capacity(n);
+  let mut u: Vec<E::G1Projective> = Vec::with_capacity(n);
+  a.push(list_g1_tau_i[n - 1].into_projective());
+  u.push(E::G1Projective::zero());
+  for d in 1..n {
+    let p = list_g1_tau_i[d - 1];
+    a.push(p.into_projective());
+    u.push(p.mul(E::Fr::from((n - d) as u64) * n_inv));
+  }
+  domain.fft_in_place(&mut a);
+  domain.fft_in_place(&mut u);
+
+  // entry i of the result is entry -i mod n of the DFT
+  a[1..].reverse();
+  u[1..].reverse();
+  let a = E::G1Projective::batch_normalization_into_affine(&a);
+  let u = E::G1Projective::batch_normalization_into_affine(&u);
+  Ok(a.into_iter().zip(u).map(|(a_i, u_i)| UpdateKey { a_i, u_i }).collect())
+}
+
+impl<E: PairingEngine> Parameters<E> {
+  // Builds the parameters from g^(τ^i), i ∈ [0, n] and g2^(τ^i) alone, so τ never has
+  // to be known, e.g. from the output of a setup ceremony. n must be a domain size.
+  pub fn from_powers(
+    list_g1_tau_i: Vec<E::G1Affine>,
+    list_g2_tau_i: Vec<E::G2Affine>,
+  ) -> Result<Self, CustomError> {
+    if list_g2_tau_i.len() < 2 {
+      return Err(CustomError::MalformedKey);
+    }
+    let (n, _) = powers_domain::<E>(&list_g1_tau_i)?;
+
+    // a = g^(τ^n - 1); with τ^n = 1, τ is a root of unity anyone can find
+    let a = list_g1_tau_i[n].into_projective().sub(&list_g1_tau_i[0].into_projective());
+    if a.is_zero() {
+      return Err(CustomError::InvalidParameters(ParameterCheck::A));
+    }
+
+    let list_l_i = lagrange_keys::<E>(&list_g1_tau_i)?;
+    let update_keys = update_keys::<E>(&list_g1_tau_i)?;
+
+    Ok(Parameters::<E> {
+      proving_key: ProvingKey::<E> {
+        list_g1_tau_i: list_g1_tau_i.clone(),
+        list_l_i,
+        list_update_keys: update_keys,
+        hiding_key: None,
+      },
+      verifying_key: VerifyingKey::<E> {
+        list_g1_tau_i,
+        list_g2_tau_i,
+        a: a.into_affine(),
+        n: n as u64,
+        list_h_tau_i: None,
+      },
+    })
+  }
+
+  // Parameters for vectors of size n (a domain size, at most the current one) and
+  // openings of at most max_subvector positions. The proving key keeps g^(τ^i), i ∈ [0, n]
+  // and gets the l_i and upk_i of the smaller domain; the verifying key keeps only the
+  // max_subvector powers in G1 and max_subvector+1 in G2 that verify_pos needs.
+  // A hiding key is trimmed the same way.
+  pub fn trim(&self, n: usize, max_subvector: usize) -> Result<Self, CustomError> {
+    let (prk, vrk) = (&self.proving_key, &self.verifying_key);
+    let size = vrk.size()?;
+    if prk.list_g1_tau_i.len() != size + 1 {
+      return Err(CustomError::MalformedKey);
+    }
+    if n > size {
+      return Err(CustomError::VectorTooLong { size: n, max: size });
+    }
+    if max_subvector == 0 {
+      return Err(CustomError::EmptyIndexSet);
+    }
+    if max_subvector > n {
+      return Err(CustomError::SubvectorTooLarge { size: max_subvector, max: n });
+    }
+    check_subvector_size(vrk, max_subvector)?;
+
+    let list_g1_tau_i = prk.list_g1_tau_i[..n + 1].to_vec();
+    let (list_l_i, list_update_keys) = if n == size {
+      (prk.list_l_i.clone(), prk.list_update_keys.clone())
+    } else {
+      (lagrange_keys::<E>(&list_g1_tau_i)?, update_keys::<E>(&list_g1_tau_i)?)
+    };
+
+    let hiding_key = match &prk.hiding_key {
+      Some(key) => {
+        if key.list_h_tau_i.len() != size + 1 {
+          return Err(CustomError::MalformedKey);
+        }
+        let list_h_tau_i = key.list_h_tau_i[..n + 1].to_vec();
+        let list_h_l_i =
+          if n == size { key.list_h_l_i.clone() } else { lagrange_keys::<E>(&list_h_tau_i)? };
+        Some(HidingKey::<E> { list_h_tau_i, list_h_l_i })
+      }
+      None => None,
+    };
+
+    // a = g^(τ^n - 1)
+    let a = list_g1_tau_i[n].into_projective().sub(&list_g1_tau_i[0].into_projective());
+
+    Ok(Parameters::<E> {
+      verifying_key: VerifyingKey::<E> {
+        list_g1_tau_i: list_g1_tau_i[..max_subvector].to_vec(),
+        list_g2_tau_i: vrk.list_g2_tau_i[..max_subvector + 1].to_vec(),
+        a: a.into_affine(),
+        n: n as u64,
+        list_h_tau_i: hiding_key.as_ref().map(|key| key.list_h_tau_i[..max_subvector].to_vec()),
+      },
+      proving_key: ProvingKey::<E> {
+        list_g1_tau_i,
+        list_l_i,
+        list_update_keys,
+        hiding_key,
+      },
+    })
+  }
+}
+
 // TT l_i^(v_i)
 pub fn commit<E: PairingEngine>(
   prk_params: &ProvingKey<E>,
   values: Vec<E::Fr>,
-) -> Result<Commitment<E>, Error> {
+) -> Result<Commitment<E>, CustomError> {
 
   let num_coefficient = values.len();
   let num_powers = prk_params.list_l_i.len();
 
-  assert!(num_coefficient >= 1);
-  assert!(num_coefficient <= num_powers);
+  if num_coefficient == 0 {
+    return Err(CustomError::EmptyVector);
+  }
+  if num_coefficient > num_powers {
+    return Err(CustomError::VectorTooLong { size: num_coefficient, max: num_powers });
+  }
 
   let scalars: Vec<<E::Fr as PrimeField>::BigInt> =
     values.iter().map(|v| v.into_repr()).collect();
@@ -205,47 +638,64 @@ pub fn commit<E: PairingEngine>(
   Ok(c)
 }
 
+// hiding commitment c = TT l_i^(v_i) · TT hl_i^(b_i), b_i uniform for every i ∈ [0, n).
+// Returns c with the blinding b, which the prover keeps to open c later.
+// Updates work as for commit: b does not change.
+pub fn commit_hiding<E: PairingEngine, R: Rng>(
+  prk_params: &ProvingKey<E>,
+  values: Vec<E::Fr>,
+  rng: &mut R,
+) -> Result<(Commitment<E>, Vec<E::Fr>), CustomError> {
+  let key = prk_params.hiding_key.as_ref().ok_or(CustomError::MissingHidingKey)?;
+  if key.list_h_l_i.len() != prk_params.list_l_i.len() {
+    return Err(CustomError::MalformedKey);
+  }
+  let blinding: Vec<E::Fr> = (0..key.list_h_l_i.len()).map(|_| E::Fr::rand(rng)).collect();
+  let commit = commit(prk_params, values)?;
+
+  let scalars: Vec<<E::Fr as PrimeField>::BigInt> =
+    blinding.iter().map(|b| b.into_repr()).collect();
+  let mut c = VariableBaseMSM::multi_scalar_mul(&key.list_h_l_i, &scalars);
+  c.add_assign_mixed(&commit.commit);
+
+  Ok((Commitment::<E> { commit: c.into_affine() }, blinding))
+}
+
 pub fn prove_pos<E: PairingEngine>(
   prk_params: &ProvingKey<E>,
   values: Vec<E::Fr>,
   points: Vec<u32>,
 ) -> Result<Proof<E>, CustomError> {
-  let mut values = values.clone();
-  let domain: GeneralEvaluationDomain<E::Fr> =
-    EvaluationDomain::<E::Fr>::new(prk_params.list_g1_tau_i.len()-1)
-      .ok_or(CustomError::UnexpectedError)?;
-  domain.ifft_in_place(&mut values);
+  let n = prk_params.list_l_i.len();
+  let domain: GeneralEvaluationDomain<E::Fr> = new_domain(n)?;
+  prove_pos_with(prk_params, values, &points, &domain, Roots::Omega(group_gen::<E>(&domain)))
+}
 
-  // compile error without use 'UVPolynomial' - Univariate
-  // from_coefficients_vec : Constructs a new polynomial from a list of coefficients.
-  // phi(x)
-  let polynomial = DensePolynomial::from_coefficients_vec(values);
+pub(crate) fn prove_pos_with<E: PairingEngine>(
+  prk_params: &ProvingKey<E>,
+  values: Vec<E::Fr>,
+  points: &[u32],
+  domain: &GeneralEvaluationDomain<E::Fr>,
+  roots: Roots<E::Fr>,
+) -> Result<Proof<E>, CustomError> {
+  let n = prk_params.list_l_i.len();
+  if values.is_empty() {
+    return Err(CustomError::EmptyVector);
+  }
+  if values.len() > n {
+    return Err(CustomError::VectorTooLong { size: values.len(), max: n });
+  }
+  if prk_params.list_g1_tau_i.len() < n + 1 {
+    return Err(CustomError::MalformedKey);
+  }
+  check_points(points, n)?;
 
   // ∏(x-ω^i)
-  let mut divisor_polynomial = 
-    DensePolynomial::from_coefficients_vec(vec![E::Fr::one()]);
-  for point in points.iter() {
-    let tpoly = DensePolynomial::from_coefficients_vec(vec![
-      group_gen::<E>(&domain).pow(&[*point as u64]).neg(),  // compile error without core::ops::Neg
-      E::Fr::one(),
-    ]); 
-    divisor_polynomial = divisor_polynomial.mul(&tpoly);  // compile eror without core::ops::Mul
-  };
+  let tree = points_tree(points, roots);
 
-  // Φ(x) / A_I(x) = q(x) ... r(x)  (quotient / remainder)
-  let dense_or_sparse_poly: DenseOrSparsePolynomial<'_, E::Fr> = polynomial.into();
-  let dense_or_sparse_divisor : DenseOrSparsePolynomial<'_, E::Fr> = divisor_polynomial.into();
-  // Divide self by another (sparse or dense) polynomial, and returns the quotient and remainder.
-  let (witness_polynomial, _) = dense_or_sparse_poly
-    .divide_with_q_and_r(&dense_or_sparse_divisor)  
-    .unwrap();
-  
   // π = g_1^q(τ)
-  // ??? where tau came from and how to apply ??? <-- Already calucated with list_g1_tau_i
-  let scalars: Vec<<E::Fr as PrimeField>::BigInt> = 
-    witness_polynomial.iter().map(|v| v.into_repr()).collect();
-  let witness = VariableBaseMSM::multi_scalar_mul(&prk_params.list_g1_tau_i, &scalars);
-  
+  let witness = witness_term(&prk_params.list_g1_tau_i, values, &tree, domain);
+
   let proof = Proof::<E> {
     w: witness.into_affine(),
   };
@@ -253,66 +703,279 @@ pub fn prove_pos<E: PairingEngine>(
   Ok(proof)
 }
 
-// VC.VerifyPos(vrk, c, vI , I, πI )
-pub fn verify_pos<E: PairingEngine> (
+// π_I = g^q(τ) · h^q̂(τ), q̂ the quotient of the blinding polynomial by A_I.
+// The opener reveals b_i, i ∈ I, alongside v_i.
+pub fn prove_pos_hiding<E: PairingEngine>(
+  prk_params: &ProvingKey<E>,
+  values: Vec<E::Fr>,
+  blinding: Vec<E::Fr>,
+  points: Vec<u32>,
+) -> Result<Proof<E>, CustomError> {
+  let n = prk_params.list_l_i.len();
+  let domain: GeneralEvaluationDomain<E::Fr> = new_domain(n)?;
+  prove_pos_hiding_with(
+    prk_params, values, blinding, &points, &domain, Roots::Omega(group_gen::<E>(&domain)),
+  )
+}
+
+pub(crate) fn prove_pos_hiding_with<E: PairingEngine>(
+  prk_params: &ProvingKey<E>,
+  values: Vec<E::Fr>,
+  blinding: Vec<E::Fr>,
+  points: &[u32],
+  domain: &GeneralEvaluationDomain<E::Fr>,
+  roots: Roots<E::Fr>,
+) -> Result<Proof<E>, CustomError> {
+  let n = prk_params.list_l_i.len();
+  let key = prk_params.hiding_key.as_ref().ok_or(CustomError::MissingHidingKey)?;
+  if values.is_empty() {
+    return Err(CustomError::EmptyVector);
+  }
+  if values.len() > n {
+    return Err(CustomError::VectorTooLong { size: values.len(), max: n });
+  }
+  check_len(n, blinding.len())?;
+  if prk_params.list_g1_tau_i.len() < n + 1 || key.list_h_tau_i.len() < n + 1 {
+    return Err(CustomError::MalformedKey);
+  }
+  check_points(points, n)?;
+
+  let tree = points_tree(points, roots);
+  let mut witness = witness_term(&prk_params.list_g1_tau_i, values, &tree, domain);
+  witness.add_assign(&witness_term(&key.list_h_tau_i, blinding, &tree, domain));
+
+  Ok(Proof::<E> { w: witness.into_affine() })
+}
+
+// g^q(τ) for the quotient q of φ(x) = q(x)A_I(x) + R_I(x), φ interpolating values
+fn witness_term<G: AffineCurve>(
+  bases: &[G],
+  values: Vec<G::ScalarField>,
+  tree: &SubproductTree<G::ScalarField>,
+  domain: &GeneralEvaluationDomain<G::ScalarField>,
+) -> G::Projective {
+  let mut values = values;
+  domain.ifft_in_place(&mut values);
+
+  // compile error without use 'UVPolynomial' - Univariate
+  // from_coefficients_vec : Constructs a new polynomial from a list of coefficients.
+  // phi(x)
+  let polynomial = DensePolynomial::from_coefficients_vec(values);
+
+  // Φ(x) / A_I(x) = q(x) ... r(x)  (quotient / remainder)
+  let (witness_polynomial, _) = subproduct_tree::divide(&polynomial, tree.vanishing_polynomial());
+
+  // ??? where tau came from and how to apply ??? <-- Already calucated with list_g1_tau_i
+  msm_poly(bases, &witness_polynomial)
+}
+
+// all n single position proofs at once (Feist–Khovratovich)
+// π_i = g^((φ(τ)-v_i)/(τ-ω^i)) = ∏_t h_t^(ω^(it)),  h_t = ∏_{k>t} (g^(τ^(k-1-t)))^(f_k)
+// where f_k are the coefficients of φ(x).
+// h = T·s for the Toeplitz matrix T of the f_k and s = (g^(τ^0), .., g^(τ^(n-2))),
+// computed as a circular convolution of length 2n; π is then a DFT of h.
+pub fn prove_all<E: PairingEngine>(
+  prk_params: &ProvingKey<E>,
+  values: Vec<E::Fr>,
+) -> Result<Vec<Proof<E>>, CustomError> {
+  let n = prk_params.list_l_i.len();
+  let domain: GeneralEvaluationDomain<E::Fr> = new_domain(n)?;
+  prove_all_with(prk_params, values, &domain)
+}
+
+pub(crate) fn prove_all_with<E: PairingEngine>(
+  prk_params: &ProvingKey<E>,
+  values: Vec<E::Fr>,
+  domain: &GeneralEvaluationDomain<E::Fr>,
+) -> Result<Vec<Proof<E>>, CustomError> {
+  let n = prk_params.list_l_i.len();
+  if values.is_empty() {
+    return Err(CustomError::EmptyVector);
+  }
+  if values.len() > n {
+    return Err(CustomError::VectorTooLong { size: values.len(), max: n });
+  }
+  if prk_params.list_g1_tau_i.len() < n + 1 {
+    return Err(CustomError::MalformedKey);
+  }
+
+  let domain_2n: GeneralEvaluationDomain<E::Fr> =
+    EvaluationDomain::<E::Fr>::new(2 * n).ok_or(CustomError::UnsupportedDomainSize(2 * n))?;
+  if domain.size() != n || domain_2n.size() != 2 * n {
+    return Err(CustomError::UnsupportedDomainSize(n));
+  }
+
+  // f_k, padded to 2n
+  let mut coefficients = domain.ifft(&values);
+  coefficients.resize(2 * n, E::Fr::zero());
+  domain_2n.fft_in_place(&mut coefficients);
+
+  // s reversed, so that h_t is the coefficient of x^(t+n-1) in f(x)·s_rev(x)
+  let mut h: Vec<E::G1Projective> = (0..n - 1)
+    .map(|t| prk_params.list_g1_tau_i[n - 2 - t].into_projective())
+    .collect();
+  h.resize(2 * n, E::G1Projective::zero());
+  domain_2n.fft_in_place(&mut h);
+  for (h_t, f_t) in h.iter_mut().zip(coefficients.iter()) {
+    h_t.mul_assign(*f_t);
+  }
+  domain_2n.ifft_in_place(&mut h);
+
+  // deg(f·s_rev) <= 2n-3, so the circular convolution has no wrap-around
+  let mut h: Vec<E::G1Projective> = h.drain(n - 1..2 * n - 2).collect();
+  h.push(E::G1Projective::zero());
+  domain.fft_in_place(&mut h);
+
+  let proofs = E::G1Projective::batch_normalization_into_affine(&h)
+    .into_iter()
+    .map(|w| Proof::<E> { w })
+    .collect();
+
+  Ok(proofs)
+}
+
+// g^p(τ) from the powers g^(τ^i)
+fn msm_poly<G: AffineCurve>(bases: &[G], poly: &DensePolynomial<G::ScalarField>) -> G::Projective {
+  // Returns the underlying representation of the prime field element.
+  // fn into_repr(&self) -> Self::BigInt
+  let scalars: Vec<<G::ScalarField as PrimeField>::BigInt> =
+    poly.iter().map(|v| v.into_repr()).collect();
+  VariableBaseMSM::multi_scalar_mul(bases, &scalars)
+}
+
+// checks an opening (v_I, I) against the verifying key and builds the tree over ω^i, i ∈ I
+fn opening_tree<E: PairingEngine>(
+  vrk_params: &VerifyingKey<E>,
+  point_values: &[E::Fr],
+  points: &[u32],
+  roots: Roots<E::Fr>,
+) -> Result<SubproductTree<E::Fr>, CustomError> {
+  if vrk_params.list_g1_tau_i.is_empty() || vrk_params.list_g2_tau_i.is_empty() {
+    return Err(CustomError::MalformedKey);
+  }
+  let n = vrk_params.size()?;
+  check_len(points.len(), point_values.len())?;
+  check_points(points, n)?;
+  check_subvector_size(vrk_params, points.len())?;
+
+  Ok(points_tree(points, roots))
+}
+
+// R_I needs |I| powers in G1, A_I needs |I|+1 powers in G2
+fn check_subvector_size<E: PairingEngine>(
+  vrk_params: &VerifyingKey<E>,
+  size: usize,
+) -> Result<(), CustomError> {
+  if vrk_params.list_g2_tau_i.is_empty() {
+    return Err(CustomError::MalformedKey);
+  }
+  let max = vrk_params.list_g1_tau_i.len().min(vrk_params.list_g2_tau_i.len() - 1);
+  if size > max {
+    return Err(CustomError::SubvectorTooLarge { size, max });
+  }
+  Ok(())
+}
+
+// g^R_I(τ)
+fn interpolation_term<G: AffineCurve>(
+  bases: &[G],
+  tree: &SubproductTree<G::ScalarField>,
+  point_values: &[G::ScalarField],
+) -> Result<G::Projective, CustomError> {
+  // r(x) = ∑(l_i * v_i) = ∑(A_I(x) * v_i)/(A_I'(ω^i)(x - ω_i))
+  // fails only if ω^i repeats, i.e. omega is not of order n
+  let r_polynomial = tree.interpolate(point_values).ok_or(CustomError::UnexpectedError)?;
+  Ok(msm_poly(bases, &r_polynomial))
+}
+
+// (g^R_I(τ), g^A_I(τ)) of the opening check e(c/g^R_I(τ), g) = e(π_I, g^A_I(τ))
+pub(crate) fn opening_terms<E: PairingEngine>(
+  vrk_params: &VerifyingKey<E>,
+  point_values: &[E::Fr],
+  points: &[u32],
+  roots: Roots<E::Fr>,
+) -> Result<(E::G1Projective, E::G2Projective), CustomError> {
+  // A_I(x) = ∏(x - ω^i)
+  let tree = opening_tree(vrk_params, point_values, points, roots)?;
+
+  //g^RI (τ)
+  let r_value = interpolation_term(&vrk_params.list_g1_tau_i, &tree, point_values)?;
+
+  // A_I(τ) = ∏(τ - ω^i)  --> // A_I(x) = ∏(x - ω^i)
+  let a_value = msm_poly(&vrk_params.list_g2_tau_i, tree.vanishing_polynomial());
+
+  Ok((r_value, a_value))
+}
+
+// e(c/(g^R_I(τ)·h^R̂_I(τ)), g) = e(π_I, g^A_I(τ)), R̂_I interpolating the blindings b_i, i ∈ I
+pub fn verify_pos_hiding<E: PairingEngine> (
   vrk_params: &VerifyingKey<E>,
   commit: &Commitment<E>,
   point_values: Vec<E::Fr>,
+  point_blindings: Vec<E::Fr>,
   points: Vec<u32>,
   proof: &Proof<E>,
-  omega: E::Fr,         // ?? domain??
+  omega: E::Fr,
 ) -> Result<bool, CustomError> {
+  verify_pos_hiding_with(
+    vrk_params, commit, &point_values, &point_blindings, &points, proof, Roots::Omega(omega),
+  )
+}
 
-  // A_I(x) = ∏(x - ω^i)
-  let mut a_polynomial = DensePolynomial::from_coefficients_vec(vec![E::Fr::one()]);
-  for point in points.iter() {
-    let tpoly = DensePolynomial::from_coefficients_vec(vec![
-      omega.pow(&[*point as u64]).neg(),
-      E::Fr::one(), // x - first 
-    ]);
-    a_polynomial = a_polynomial.mul(&tpoly);
-  };
+pub(crate) fn verify_pos_hiding_with<E: PairingEngine> (
+  vrk_params: &VerifyingKey<E>,
+  commit: &Commitment<E>,
+  point_values: &[E::Fr],
+  point_blindings: &[E::Fr],
+  points: &[u32],
+  proof: &Proof<E>,
+  roots: Roots<E::Fr>,
+) -> Result<bool, CustomError> {
+  let list_h_tau_i = vrk_params.list_h_tau_i.as_ref().ok_or(CustomError::MissingHidingKey)?;
+  check_len(points.len(), point_blindings.len())?;
+  let tree = opening_tree(vrk_params, point_values, points, roots)?;
+  if list_h_tau_i.len() < points.len() {
+    return Err(CustomError::SubvectorTooLarge { size: points.len(), max: list_h_tau_i.len() });
+  }
 
-  // r(x) = ∑（l_i * v_i） = ∑（A_I(x) * v_i）/(A_I'(ω^i)(x - ω_i))
-  let mut r_polynomial = DensePolynomial::from_coefficients_vec(vec![E::Fr::zero()]);
-  for (point, value) in points.iter().zip(point_values.iter()) {
-    // x - ω_i
-    let tpoly = DensePolynomial::from_coefficients_vec(vec![
-      omega.pow(&[*point as u64]).neg(),
-      E::Fr::one(),
-    ]);
-    // A_I(x)/(x - ω_i)
-    let mut l_polynomial = a_polynomial.div(&tpoly);
-    // A_I'(ω^i)
-    let b_aside = l_polynomial.evaluate(&omega.pow(&[*point as u64]));
+  let mut inner = commit.commit.into_projective();
+  inner.sub_assign(&interpolation_term(&vrk_params.list_g1_tau_i, &tree, point_values)?);
+  inner.sub_assign(&interpolation_term(list_h_tau_i, &tree, point_blindings)?);
+  let a_value = msm_poly(&vrk_params.list_g2_tau_i, tree.vanishing_polynomial());
 
-    // v_i/A_I'(ω^i)
-    let bpoly = DensePolynomial::from_coefficients_vec(vec![value.div(&b_aside)]);
+  let lhs = E::pairing(inner, vrk_params.list_g2_tau_i[0]);
+  let rhs = E::pairing(proof.w, a_value);
 
-    // (A_I(x) /(x - ω_i)) * (v_i/(A_I'(ω^i))
-    l_polynomial = l_polynomial.mul(&bpoly);
-    
-    r_polynomial = r_polynomial.add(l_polynomial);  // compile error without core::ops::Add
-  };
+  Ok(lhs == rhs)
+}
 
-  // Returns the underlying representation of the prime field element.
-  // fn into_repr(&self) -> Self::BigInt
-  let scalars: Vec<<E::Fr as PrimeField>::BigInt> = 
-    r_polynomial.iter().map(|v| v.into_repr()).collect(); 
-  
-  //g^RI (τ)
-  let r_value = VariableBaseMSM::multi_scalar_mul(&vrk_params.list_g1_tau_i, &scalars);
+// VC.VerifyPos(vrk, c, vI , I, πI )
+pub fn verify_pos<E: PairingEngine> (
+  vrk_params: &VerifyingKey<E>,
+  commit: &Commitment<E>,
+  point_values: Vec<E::Fr>,
+  points: Vec<u32>,
+  proof: &Proof<E>,
+  omega: E::Fr,         // ?? domain??
+) -> Result<bool, CustomError> {
+  verify_pos_with(vrk_params, commit, &point_values, &points, proof, Roots::Omega(omega))
+}
+
+pub(crate) fn verify_pos_with<E: PairingEngine> (
+  vrk_params: &VerifyingKey<E>,
+  commit: &Commitment<E>,
+  point_values: &[E::Fr],
+  points: &[u32],
+  proof: &Proof<E>,
+  roots: Roots<E::Fr>,
+) -> Result<bool, CustomError> {
+  let (r_value, a_value) = opening_terms(vrk_params, point_values, points, roots)?;
 
   // e(c/g^R_I(τ), g) = e(π_I , g^A_I(τ)).
   let mut inner = commit.commit.into_projective();
   inner.sub_assign(&r_value); // x -= 1 // G1Projective - why sub , not div?? 
   let lhs = E::pairing(inner, vrk_params.list_g2_tau_i[0]);
 
-  // A_I(τ) = ∏(τ - ω^i)  --> // A_I(x) = ∏(x - ω^i)
-  let a_scalars: Vec<<E::Fr as PrimeField>::BigInt> =
-    a_polynomial.iter().map(|v| v.into_repr()).collect();
-  let a_value = VariableBaseMSM::multi_scalar_mul(&vrk_params.list_g2_tau_i, &a_scalars);
-
   let rhs = E::pairing(proof.w, a_value);
 
   Ok(lhs == rhs)
@@ -326,9 +989,24 @@ pub fn verify_upk<E: PairingEngine> (
   upk: &UpdateKey<E>,
   omega: E::Fr,
 ) -> Result<bool, CustomError> {
+  verify_upk_with(vrk_params, point, upk, Roots::Omega(omega))
+}
+
+pub(crate) fn verify_upk_with<E: PairingEngine> (
+  vrk_params: &VerifyingKey<E>,
+  point: u32,
+  upk: &UpdateKey<E>,
+  roots: Roots<E::Fr>,
+) -> Result<bool, CustomError> {
+  if vrk_params.list_g1_tau_i.is_empty() || vrk_params.list_g2_tau_i.len() < 2 {
+    return Err(CustomError::MalformedKey);
+  }
+  let n = vrk_params.size()?;
+  check_points(&[point], n)?;
+
   // e(a_i, g^i/g^(w^i)) = e(a,g)
     // to prove that w^i is a root of X^n -1
-  let omega_i = omega.pow(&[point as u64]);
+  let omega_i = roots.at(point);
 
   // g^i/g^(w^i)
   let inner = vrk_params.list_g2_tau_i[1].into_projective().sub(
@@ -342,9 +1020,7 @@ pub fn verify_upk<E: PairingEngine> (
 
   //e(l_i/g1, g) = e(u_i  , g^τ /g(ω_i))
   //a_i^(1/A'(ω^i))
-  let n = vrk_params.list_g1_tau_i.len() - 1;
-  let a_aside_omega_i_divisor = omega.pow(&[point as u64])
-                          .div(&E::Fr::from_repr((n as u64).into()).unwrap());  // ??????
+  let a_aside_omega_i_divisor = omega_i.mul(&n_inverse::<E::Fr>(n)?);
   let l_value = upk.a_i.mul(a_aside_omega_i_divisor);
 
   let inner2 = l_value.sub(&vrk_params.list_g1_tau_i[0].into_projective());
@@ -356,6 +1032,74 @@ pub fn verify_upk<E: PairingEngine> (
   Ok(rs1 && rs2)
 }
 
+// Checks every upk_i of a proving key, and l_i against them, with two pairings.
+// With random ρ_i, ρ'_i, σ_i the checks of verify_upk
+//   e(a_i, g2^τ/g2^(ω^i)) = e(a, g2),  e(l_i/g, g2) = e(u_i, g2^τ/g2^(ω^i))
+// fold into e(∑ ρ_i(ω^i a_i + a) + ρ'_i(l_i/g + ω^i u_i), g2) = e(∑ ρ_i a_i + ρ'_i u_i, g2^τ)
+// and l_i = a_i^(ω^i/n) into ∑ σ_i(l_i - (ω^i/n)a_i) = 0.
+pub fn verify_all_upks<E: PairingEngine, R: Rng> (
+  vrk_params: &VerifyingKey<E>,
+  prk_params: &ProvingKey<E>,
+  omega: E::Fr,
+  rng: &mut R,
+) -> Result<bool, CustomError> {
+  verify_all_upks_with(vrk_params, prk_params, Roots::Omega(omega), rng)
+}
+
+pub(crate) fn verify_all_upks_with<E: PairingEngine, R: Rng> (
+  vrk_params: &VerifyingKey<E>,
+  prk_params: &ProvingKey<E>,
+  roots: Roots<E::Fr>,
+  rng: &mut R,
+) -> Result<bool, CustomError> {
+  if vrk_params.list_g1_tau_i.is_empty() || vrk_params.list_g2_tau_i.len() < 2 {
+    return Err(CustomError::MalformedKey);
+  }
+  let n = vrk_params.size()?;
+  check_len(n, prk_params.list_update_keys.len())?;
+  check_len(n, prk_params.list_l_i.len())?;
+  let n_inv = n_inverse::<E::Fr>(n)?;
+
+  let mut x_bases = Vec::with_capacity(4 * n + 2);
+  let mut x_scalars = Vec::with_capacity(4 * n + 2);
+  let mut y_bases = Vec::with_capacity(2 * n);
+  let mut y_scalars = Vec::with_capacity(2 * n);
+  let mut l_bases = Vec::with_capacity(2 * n);
+  let mut l_scalars = Vec::with_capacity(2 * n);
+  let (mut rho_sum, mut rho_prime_sum) = (E::Fr::zero(), E::Fr::zero());
+  let keys = prk_params.list_update_keys.iter().zip(prk_params.list_l_i.iter());
+  for (i, (upk, l_i)) in keys.enumerate() {
+    let omega_i = roots.at(i as u32);
+    let (rho, rho_prime, sigma) = (E::Fr::rand(rng), E::Fr::rand(rng), E::Fr::rand(rng));
+    rho_sum += &rho;
+    rho_prime_sum += &rho_prime;
+
+    x_bases.extend_from_slice(&[upk.a_i, *l_i, upk.u_i]);
+    x_scalars.extend_from_slice(&[
+      (rho * omega_i).into_repr(),
+      rho_prime.into_repr(),
+      (rho_prime * omega_i).into_repr(),
+    ]);
+    y_bases.extend_from_slice(&[upk.a_i, upk.u_i]);
+    y_scalars.extend_from_slice(&[rho.into_repr(), rho_prime.into_repr()]);
+    l_bases.extend_from_slice(&[*l_i, upk.a_i]);
+    l_scalars.extend_from_slice(&[sigma.into_repr(), (-(sigma * omega_i * n_inv)).into_repr()]);
+  }
+  x_bases.extend_from_slice(&[vrk_params.a, vrk_params.list_g1_tau_i[0]]);
+  x_scalars.extend_from_slice(&[rho_sum.into_repr(), (-rho_prime_sum).into_repr()]);
+
+  if !VariableBaseMSM::multi_scalar_mul(&l_bases, &l_scalars).is_zero() {
+    return Ok(false);
+  }
+  let x = VariableBaseMSM::multi_scalar_mul(&x_bases, &x_scalars);
+  let y = VariableBaseMSM::multi_scalar_mul(&y_bases, &y_scalars);
+  let pairs = [
+    (x.into_affine().into(), vrk_params.list_g2_tau_i[0].into()),
+    (y.into_affine().neg().into(), vrk_params.list_g2_tau_i[1].into()),
+  ];
+  Ok(E::product_of_pairings(pairs.iter()).is_one())
+}
+
 //(c, δ, j, upk_j)
 // c' = c·(l_j )^δ, where l_j = a_j^(1/A'(ω^j))
 pub fn update_commit<E: PairingEngine> (
@@ -366,9 +1110,20 @@ pub fn update_commit<E: PairingEngine> (
   omega: E::Fr,
   n: usize, // ??
 ) -> Result<Commitment<E>, CustomError> {
+  update_commit_with(commit, delta, point, upk, Roots::Omega(omega), n)
+}
 
-  let a_aside_omega_i_divisor = omega.pow(&[point as u64])
-      .div(&E::Fr::from_repr((n as u64).into()).unwrap());  //??
+pub(crate) fn update_commit_with<E: PairingEngine> (
+  commit: &Commitment<E>,
+  delta: E::Fr,
+  point: u32,
+  upk: &UpdateKey<E>,
+  roots: Roots<E::Fr>,
+  n: usize,
+) -> Result<Commitment<E>, CustomError> {
+  check_points(&[point], n)?;
+
+  let a_aside_omega_i_divisor = roots.at(point).mul(&n_inverse::<E::Fr>(n)?);
   let l_value = upk.a_i.mul(a_aside_omega_i_divisor);
 
   let new_commit = commit.commit.into_projective().add(&(l_value.mul(delta.into_repr()))); // compile error witn into
@@ -381,7 +1136,48 @@ pub fn update_commit<E: PairingEngine> (
 
 }
 
+// Applies v_j += δ_j for every (j, δ_j) in updates with one MSM:
+// c' = c·∏ l_j^(δ_j), l_j = a_j^(ω^j/n). upks holds upk_j for every update, in order;
+// a position may appear more than once, its deltas add up.
+pub fn update_commit_batch<E: PairingEngine> (
+  commit: &Commitment<E>,
+  updates: &[(u32, E::Fr)],
+  upks: &[UpdateKey<E>],
+  omega: E::Fr,
+  n: usize,
+) -> Result<Commitment<E>, CustomError> {
+  update_commit_batch_with(commit, updates, upks, Roots::Omega(omega), n)
+}
+
+pub(crate) fn update_commit_batch_with<E: PairingEngine> (
+  commit: &Commitment<E>,
+  updates: &[(u32, E::Fr)],
+  upks: &[UpdateKey<E>],
+  roots: Roots<E::Fr>,
+  n: usize,
+) -> Result<Commitment<E>, CustomError> {
+  check_len(updates.len(), upks.len())?;
+  for (point, _) in updates.iter() {
+    if *point as usize >= n {
+      return Err(CustomError::IndexOutOfRange { index: *point, size: n });
+    }
+  }
+
+  let n_inv = n_inverse::<E::Fr>(n)?;
+  let bases: Vec<E::G1Affine> = upks.iter().map(|upk| upk.a_i).collect();
+  let scalars: Vec<<E::Fr as PrimeField>::BigInt> = updates
+    .iter()
+    .map(|(point, delta)| (roots.at(*point) * n_inv * delta).into_repr())
+    .collect();
+  let mut new_commit = VariableBaseMSM::multi_scalar_mul(&bases, &scalars);
+  new_commit.add_assign_mixed(&commit.commit);
+
+  Ok(Commitment::<E> {
+    commit: new_commit.into_affine(),
+  })
+}
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_proof<E: PairingEngine> (
   proof: &Proof<E>,
   delta: E::Fr,
@@ -391,26 +1187,40 @@ pub fn update_proof<E: PairingEngine> (
   upk_j: &UpdateKey<E>,
   omega: E::Fr,
   n: usize,
-) -> Result<Proof<E>, Error> {
+) -> Result<Proof<E>, CustomError> {
+  update_proof_with(proof, delta, point_i, point_j, upk_i, upk_j, Roots::Omega(omega), n)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_proof_with<E: PairingEngine> (
+  proof: &Proof<E>,
+  delta: E::Fr,
+  point_i: u32,
+  point_j: u32,
+  upk_i: &UpdateKey<E>,
+  upk_j: &UpdateKey<E>,
+  roots: Roots<E::Fr>,
+  n: usize,
+) -> Result<Proof<E>, CustomError> {
+  check_points(&[point_i], n)?;
+  check_points(&[point_j], n)?;
   let mut new_witness = proof.w.into_projective();
 
   if point_i == point_j {
     new_witness.add_assign(&upk_i.u_i.mul(delta));
   } else { // i =/= j
     //c_1 = 1/(ω_j - ω_i), c_2 = 1/(ω_i - ω_j)
-    let omega_i = omega.pow(&[point_i as u64]);
-    let omega_j = omega.pow(&[point_j as u64]);
+    let omega_i = roots.at(point_i);
+    let omega_j = roots.at(point_j);
 
-    let c_1 = E::Fr::one().div(&(omega_j.sub(&omega_i)));
-    let c_2 = E::Fr::one().div(&(omega_i.sub(&omega_j)));
+    let c_1 = omega_j.sub(&omega_i).inverse().ok_or(CustomError::UnexpectedError)?;
+    let c_2 = c_1.neg();
 
     // w_ij = a_j^c_1 * a_i^c2
     let w_ij = upk_j.a_i.mul(c_1).add(&upk_i.a_i.mul(c_2));
 
     // u_ij = w_ij ^ (1/A'(w^j))
-    let a_aside_omega_i_divisor = omega
-      .pow(&[point_j as u64])
-      .div(&E::Fr::from_repr((n as u64).into()).unwrap());  // why n ???
+    let a_aside_omega_i_divisor = omega_j.mul(&n_inverse::<E::Fr>(n)?);
     let u_ij = w_ij.mul(a_aside_omega_i_divisor.into_repr());  // compile error with into
     new_witness.add_assign(&u_ij.mul(delta.into_repr()));  // compile error with into
     
@@ -424,34 +1234,110 @@ pub fn update_proof<E: PairingEngine> (
 
 }
 
+// Updates an aggregated proof π_I after v_j += δ, for j in I or not.
+// π_I = ∏ π_i^(c_i) with c_i = 1/A_I'(ω^i), so it moves by the c_i-combination of the
+// single-position updates of update_proof:
+//   j ∉ I:  ∏_i (a_j/a_i)^(δ·c_i·ω^j/(n(ω^j-ω^i)))
+//   j ∈ I:  the same over i ≠ j, times u_j^(δ·c_j)
+// upks holds upk_i for every i ∈ I, in the order of points.
+#[allow(clippy::too_many_arguments)]
+pub fn update_subvector_proof<E: PairingEngine> (
+  proof: &Proof<E>,
+  delta: E::Fr,
+  points: Vec<u32>,
+  point_j: u32,
+  upks: &[UpdateKey<E>],
+  upk_j: &UpdateKey<E>,
+  omega: E::Fr,
+  n: usize,
+) -> Result<Proof<E>, CustomError> {
+  update_subvector_proof_with(proof, delta, &points, point_j, upks, upk_j, Roots::Omega(omega), n)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_subvector_proof_with<E: PairingEngine> (
+  proof: &Proof<E>,
+  delta: E::Fr,
+  points: &[u32],
+  point_j: u32,
+  upks: &[UpdateKey<E>],
+  upk_j: &UpdateKey<E>,
+  roots: Roots<E::Fr>,
+  n: usize,
+) -> Result<Proof<E>, CustomError> {
+  check_points(points, n)?;
+  check_points(&[point_j], n)?;
+  check_len(points.len(), upks.len())?;
+
+  // c_i = 1/A_I'(ω^i)
+  let mut c = points_tree(points, roots).derivative_evaluations();
+  if c.iter().any(|c_i| c_i.is_zero()) {
+    return Err(CustomError::UnexpectedError);
+  }
+  batch_inversion(&mut c);
+
+  // 1/(ω^j-ω^i), i ≠ j
+  let omega_j = roots.at(point_j);
+  let others: Vec<usize> = (0..points.len()).filter(|k| points[*k] != point_j).collect();
+  let mut divisors: Vec<E::Fr> = others.iter().map(|k| omega_j - roots.at(points[*k])).collect();
+  batch_inversion(&mut divisors);
+
+  // δ·ω^j/n
+  let scale = delta * omega_j * n_inverse::<E::Fr>(n)?;
+  let mut bases = Vec::with_capacity(points.len() + 2);
+  let mut scalars = Vec::with_capacity(points.len() + 2);
+  let mut a_j_scalar = E::Fr::zero();
+  for (k, divisor) in others.iter().zip(divisors.iter()) {
+    let t = scale * c[*k] * divisor;
+    a_j_scalar += &t;
+    bases.push(upks[*k].a_i);
+    scalars.push((-t).into_repr());
+  }
+  bases.push(upk_j.a_i);
+  scalars.push(a_j_scalar.into_repr());
+  if let Some(k) = points.iter().position(|i| *i == point_j) {
+    bases.push(upk_j.u_i);
+    scalars.push((delta * c[k]).into_repr());
+  }
+
+  let mut new_witness = VariableBaseMSM::multi_scalar_mul(&bases, &scalars);
+  new_witness.add_assign_mixed(&proof.w);
+  Ok(Proof::<E> {
+    w: new_witness.into_affine(),
+  })
+}
+
 //VC.AggregateProofs(I,(π_i)i∈I )
 pub fn aggregate_proofs<E: PairingEngine> (
   points: Vec<u32>,
   proofs: Vec<Proof<E>>,
   omega: E::Fr,
 ) -> Result<Proof<E>, CustomError> {
+  aggregate_proofs_with(&points, &proofs, Roots::Omega(omega))
+}
 
-  // A(x) = ∏(x-ω^i)
-  let mut a_polynomal = DensePolynomial::from_coefficients_vec(vec![E::Fr::one()]);
-  for point in points.iter() {
-    let tpoly = DensePolynomial::from_coefficients_vec(vec![
-      omega.pow(&[*point as u64]).neg(),
-      E::Fr::one(),
-    ]);
-    a_polynomal = a_polynomal.mul(&tpoly);
-  };
+pub(crate) fn aggregate_proofs_with<E: PairingEngine> (
+  points: &[u32],
+  proofs: &[Proof<E>],
+  roots: Roots<E::Fr>,
+) -> Result<Proof<E>, CustomError> {
+  check_len(points.len(), proofs.len())?;
+  if points.is_empty() {
+    return Err(CustomError::EmptyIndexSet);
+  }
+  check_distinct(points)?;
 
-  let mut aggregate_witness = E::G1Projective::zero();
-  for (point, proof) in points.iter().zip(proofs.iter()) {
-    let divisor_polynomial = DensePolynomial::from_coefficients_vec(vec![
-      omega.pow(&[*point as u64]).neg(),
-      E::Fr::one(),
-    ]);
-    let a_aside_polynomial = a_polynomal.div(&divisor_polynomial);
+  // A(x) = ∏(x-ω^i), c_i = 1/A'(ω^i)
+  let mut c = points_tree(points, roots).derivative_evaluations();
+  if c.iter().any(|c_i| c_i.is_zero()) {
+    return Err(CustomError::UnexpectedError);
+  }
+  batch_inversion(&mut c);
 
-    let c = E::Fr::one().div(&a_aside_polynomial.evaluate(&omega.pow(&[*point as u64])));
-    aggregate_witness.add_assign(&proof.w.mul(c));
-  };
+  // π_I = ∏ π_i^(c_i)
+  let bases: Vec<E::G1Affine> = proofs.iter().map(|proof| proof.w).collect();
+  let scalars: Vec<<E::Fr as PrimeField>::BigInt> = c.iter().map(|c_i| c_i.into_repr()).collect();
+  let aggregate_witness = VariableBaseMSM::multi_scalar_mul(&bases, &scalars);
 
   let proof = Proof::<E> {
     w: aggregate_witness.into_affine(),
@@ -459,4 +1345,274 @@ pub fn aggregate_proofs<E: PairingEngine> (
 
   Ok(proof)
 
-}
\ No newline at end of file
+}
+
+// t_j = H(c_1, I_1, v_1, .., c_m, I_m, v_m, j), binding every aggregated proof to the whole statement;
+// with a transcript, the challenges after it absorbs the same openings
+fn cross_commitment_scalars<E: PairingEngine>(
+  openings: &[Opening<E>],
+  transcript: Option<&mut Transcript>,
+) -> Result<Vec<E::Fr>, CustomError> {
+  if let Some(transcript) = transcript {
+    transcript.append(b"openings", &(openings.len() as u64))?;
+    for (commit, points, point_values) in openings.iter() {
+      transcript.append(b"commitment", commit)?;
+      transcript.append(b"points", points)?;
+      transcript.append(b"values", point_values)?;
+    }
+    return Ok(transcript.challenge_scalars(b"cross-commitment", openings.len()));
+  }
+
+  let mut statement = b"asvc-cross-commitment-aggregation".to_vec();
+  (openings.len() as u64).serialize(&mut statement)?;
+  for (commit, points, point_values) in openings.iter() {
+    commit.serialize(&mut statement)?;
+    points.serialize(&mut statement)?;
+    point_values.serialize(&mut statement)?;
+  }
+  Ok(hash_to_scalars(&statement, openings.len()))
+}
+
+// t_j = H(H(statement) || j), j ∈ [0, count)
+pub(crate) fn hash_to_scalars<F: PrimeField>(statement: &[u8], count: usize) -> Vec<F> {
+  let seed = Blake2b::digest(statement);
+  (0..count as u64)
+    .map(|j| {
+      let mut hasher = Blake2b::new();
+      hasher.update(seed);
+      hasher.update(j.to_le_bytes());
+      F::from_le_bytes_mod_order(&hasher.finalize())
+    })
+    .collect()
+}
+
+// Aggregates proofs π_j of openings (c_j, I_j, v_j) of different commitments
+// into π = ∏ π_j^(t_j), with t_j hashed from all openings (Pointproofs style).
+pub fn aggregate_across_commitments<E: PairingEngine> (
+  openings: &[Opening<E>],
+  proofs: Vec<Proof<E>>,
+) -> Result<Proof<E>, CustomError> {
+  aggregate_across_commitments_with(openings, proofs, None)
+}
+
+// t_j drawn from a transcript, which may already hold the surrounding protocol;
+// the verifier must replay it with verify_across_commitments_with_transcript
+pub fn aggregate_across_commitments_with_transcript<E: PairingEngine> (
+  openings: &[Opening<E>],
+  proofs: Vec<Proof<E>>,
+  transcript: &mut Transcript,
+) -> Result<Proof<E>, CustomError> {
+  aggregate_across_commitments_with(openings, proofs, Some(transcript))
+}
+
+fn aggregate_across_commitments_with<E: PairingEngine> (
+  openings: &[Opening<E>],
+  proofs: Vec<Proof<E>>,
+  transcript: Option<&mut Transcript>,
+) -> Result<Proof<E>, CustomError> {
+  check_len(openings.len(), proofs.len())?;
+  if openings.is_empty() {
+    return Err(CustomError::EmptyIndexSet);
+  }
+  for (_, points, point_values) in openings.iter() {
+    check_len(points.len(), point_values.len())?;
+    check_distinct(points)?;
+  }
+
+  let t = cross_commitment_scalars(openings, transcript)?;
+  let bases: Vec<E::G1Affine> = proofs.iter().map(|proof| proof.w).collect();
+  let scalars: Vec<<E::Fr as PrimeField>::BigInt> = t.iter().map(|t_j| t_j.into_repr()).collect();
+  let aggregate_witness = VariableBaseMSM::multi_scalar_mul(&bases, &scalars);
+
+  Ok(Proof::<E> {
+    w: aggregate_witness.into_affine(),
+  })
+}
+
+// With U = ∪ I_j and B_j(x) = A_U(x)/A_j(x) = ∏_{i ∈ U\I_j}(x - ω^i):
+//   (c_j/g^R_j(τ))^(B_j(τ)) = g^(q_j(τ)·A_U(τ))
+// so the aggregated proof satisfies
+//   ∏ e((c_j/g^R_j(τ))^(t_j), g^B_j(τ)) = e(π, g^A_U(τ))
+pub fn verify_across_commitments<E: PairingEngine> (
+  vrk_params: &VerifyingKey<E>,
+  openings: &[Opening<E>],
+  proof: &Proof<E>,
+  omega: E::Fr,
+) -> Result<bool, CustomError> {
+  verify_across_commitments_with(vrk_params, openings, proof, Roots::Omega(omega), None)
+}
+
+// for proofs from aggregate_across_commitments_with_transcript, given the transcript
+// in the state the prover started from
+pub fn verify_across_commitments_with_transcript<E: PairingEngine> (
+  vrk_params: &VerifyingKey<E>,
+  openings: &[Opening<E>],
+  proof: &Proof<E>,
+  omega: E::Fr,
+  transcript: &mut Transcript,
+) -> Result<bool, CustomError> {
+  verify_across_commitments_with(vrk_params, openings, proof, Roots::Omega(omega), Some(transcript))
+}
+
+pub(crate) fn verify_across_commitments_with<E: PairingEngine> (
+  vrk_params: &VerifyingKey<E>,
+  openings: &[Opening<E>],
+  proof: &Proof<E>,
+  roots: Roots<E::Fr>,
+  transcript: Option<&mut Transcript>,
+) -> Result<bool, CustomError> {
+  if openings.is_empty() {
+    return Err(CustomError::EmptyIndexSet);
+  }
+
+  // c_j/g^R_j(τ); also checks every I_j before the union is used
+  let mut inners = Vec::with_capacity(openings.len());
+  for (commit, points, point_values) in openings.iter() {
+    let tree = opening_tree(vrk_params, point_values, points, roots)?;
+    let r_value = interpolation_term(&vrk_params.list_g1_tau_i, &tree, point_values)?;
+    let mut inner = commit.commit.into_projective();
+    inner.sub_assign(&r_value);
+    inners.push(inner);
+  }
+
+  let union: BTreeSet<u32> = openings
+    .iter()
+    .flat_map(|(_, points, _)| points.iter().cloned())
+    .collect();
+  let union: Vec<u32> = union.into_iter().collect();
+  check_subvector_size(vrk_params, union.len())?;
+
+  let t = cross_commitment_scalars(openings, transcript)?;
+  let mut pairs = Vec::with_capacity(openings.len() + 1);
+  for (((_, points, _), inner), t_j) in openings.iter().zip(inners.iter()).zip(t.iter()) {
+    let inner = inner.mul(t_j.into_repr());
+
+    let complement: Vec<u32> = union.iter().filter(|i| !points.contains(i)).cloned().collect();
+    let b_value = if complement.is_empty() {
+      vrk_params.list_g2_tau_i[0]
+    } else {
+      msm_poly(&vrk_params.list_g2_tau_i, points_tree(&complement, roots).vanishing_polynomial())
+        .into_affine()
+    };
+    pairs.push((inner.into_affine().into(), b_value.into()));
+  }
+
+  let a_value = msm_poly(&vrk_params.list_g2_tau_i, points_tree(&union, roots).vanishing_polynomial());
+  pairs.push((proof.w.neg().into(), a_value.into_affine().into()));
+
+  Ok(E::product_of_pairings(pairs.iter()).is_one())
+}
+
+// proof that ∑ w_i·v_i is the value the verifier is given; weights past the end are 0
+pub fn prove_linear<E: PairingEngine> (
+  prk_params: &ProvingKey<E>,
+  values: Vec<E::Fr>,
+  weights: Vec<E::Fr>,
+) -> Result<LinearProof<E>, CustomError> {
+  let n = prk_params.list_l_i.len();
+  let domain: GeneralEvaluationDomain<E::Fr> = new_domain(n)?;
+  prove_linear_with(prk_params, values, weights, &domain)
+}
+
+pub(crate) fn prove_linear_with<E: PairingEngine> (
+  prk_params: &ProvingKey<E>,
+  values: Vec<E::Fr>,
+  weights: Vec<E::Fr>,
+  domain: &GeneralEvaluationDomain<E::Fr>,
+) -> Result<LinearProof<E>, CustomError> {
+  let n = prk_params.list_l_i.len();
+  for len in [values.len(), weights.len()] {
+    if len == 0 {
+      return Err(CustomError::EmptyVector);
+    }
+    if len > n {
+      return Err(CustomError::VectorTooLong { size: len, max: n });
+    }
+  }
+  if prk_params.list_g1_tau_i.len() < n + 1 {
+    return Err(CustomError::MalformedKey);
+  }
+
+  // φ(x)W(x), degree ≤ 2n-2
+  let phi = DensePolynomial::from_coefficients_vec(domain.ifft(&values));
+  let w = DensePolynomial::from_coefficients_vec(domain.ifft(&weights));
+  let product = &phi * &w;
+  let coeff = |k: usize| product.coeffs.get(k).cloned().unwrap_or_else(E::Fr::zero);
+
+  // q(x) = ∑ p_(k+n) x^k, and φW mod (x^n - 1) = ∑ (p_k + p_(k+n)) x^k = x·r(x) + y/n
+  let q: Vec<E::Fr> = (0..n.saturating_sub(1)).map(|k| coeff(k + n)).collect();
+  let r: Vec<E::Fr> = (1..n).map(|k| coeff(k) + coeff(k + n)).collect();
+  let mut r_shifted = vec![E::Fr::zero(); 2];
+  r_shifted.extend_from_slice(&r);
+
+  let bases = &prk_params.list_g1_tau_i;
+  Ok(LinearProof::<E> {
+    q: msm_poly(bases, &DensePolynomial::from_coefficients_vec(q)).into_affine(),
+    r: msm_poly(bases, &DensePolynomial::from_coefficients_vec(r)).into_affine(),
+    r_shifted: msm_poly(bases, &DensePolynomial::from_coefficients_vec(r_shifted)).into_affine(),
+  })
+}
+
+// e(c, g2^W(τ)) = e(g^q(τ), g2^(τ^n - 1))·e(g^r(τ), g2^τ)·e(g^(y/n), g2)
+// and e(g^r(τ), g2^(τ^2)) = e(g^(τ^2·r(τ)), g2).
+// Needs the n+1 powers in G2 of an untrimmed verifying key.
+pub fn verify_linear<E: PairingEngine> (
+  vrk_params: &VerifyingKey<E>,
+  commit: &Commitment<E>,
+  weights: Vec<E::Fr>,
+  result: E::Fr,
+  proof: &LinearProof<E>,
+) -> Result<bool, CustomError> {
+  let n = vrk_params.size()?;
+  let domain: GeneralEvaluationDomain<E::Fr> = new_domain(n)?;
+  verify_linear_with(vrk_params, commit, &weights, result, proof, &domain)
+}
+
+pub(crate) fn verify_linear_with<E: PairingEngine> (
+  vrk_params: &VerifyingKey<E>,
+  commit: &Commitment<E>,
+  weights: &[E::Fr],
+  result: E::Fr,
+  proof: &LinearProof<E>,
+  domain: &GeneralEvaluationDomain<E::Fr>,
+) -> Result<bool, CustomError> {
+  let n = domain.size();
+  if weights.is_empty() {
+    return Err(CustomError::EmptyVector);
+  }
+  if weights.len() > n {
+    return Err(CustomError::VectorTooLong { size: weights.len(), max: n });
+  }
+  let g2 = &vrk_params.list_g2_tau_i;
+  if vrk_params.list_g1_tau_i.is_empty() || g2.len() < n + 1 {
+    return Err(CustomError::MalformedKey);
+  }
+
+  // g2^W(τ) and g2^(τ^n - 1)
+  let w = DensePolynomial::from_coefficients_vec(domain.ifft(weights));
+  let w_value = msm_poly(g2, &w);
+  let z_value = g2[n].into_projective().sub(&g2[0].into_projective());
+  let y_value = vrk_params.list_g1_tau_i[0].mul(result * n_inverse::<E::Fr>(n)?);
+
+  let sum = E::product_of_pairings(
+    [
+      (commit.commit.into(), w_value.into_affine().into()),
+      (proof.q.neg().into(), z_value.into_affine().into()),
+      (proof.r.neg().into(), g2[1].into()),
+      (y_value.into_affine().neg().into(), g2[0].into()),
+    ]
+    .iter(),
+  );
+
+  // deg r ≤ n-2; for n = 1 that means r = 0
+  let degree = if n < 2 {
+    proof.r.is_zero() && proof.r_shifted.is_zero()
+  } else {
+    E::product_of_pairings(
+      [(proof.r.into(), g2[2].into()), (proof.r_shifted.neg().into(), g2[0].into())].iter(),
+    )
+    .is_one()
+  };
+
+  Ok(sum.is_one() && degree)
+}
@@ -1,5 +1,238 @@
-mod lib;
+// asvc command line tool, over BLS12-381 unless --curve names another enabled curve.
+//
+// Keys, commitments and proofs are files in the library's encoding, vectors are
+// text files with one decimal field element per line, and positions are given
+// as a comma separated list such as 0,3,5.
+// verify exits with 0 for a valid opening, 1 for an invalid one and 2 on errors.
+// Built with the cli feature.
+use ark_bls12_381::Bls12_381;
+use ark_ec::PairingEngine;
+use ark_ff::PrimeField;
+use rand_core::OsRng;
+
+use std::fmt;
+use std::fs;
+use std::process;
+use std::str::FromStr;
+
+use asvc::{Asvc, Commitment, Compression, CustomError, Encode, Parameters, Proof, VerifyingKey};
+
+const USAGE: &str = "usage: asvc [--curve bls12-381|bn254|bls12-377] <command> ...
+  (bn254 and bls12-377 need the cargo features of the same name)
+
+  asvc keygen <n> <params-out> [<verifying-key-out>]
+  asvc commit <params> <vector> <commitment-out>
+  asvc prove <params> <vector> <positions> <proof-out>
+  asvc aggregate <params> <positions> <proof-out> <proof>...
+  asvc update <params> <position> <delta> <commitment> <commitment-out>
+  asvc update <params> <position> <delta> <proof> <proof-out> --proof <proof-position>
+  asvc verify <params|verifying-key> <commitment> <positions> <values> <proof>";
+
+enum CliError {
+  Usage(String),
+  Asvc(CustomError),
+}
+
+impl fmt::Display for CliError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      CliError::Usage(message) => write!(f, "{}\n\n{}", message, USAGE),
+      CliError::Asvc(e) => write!(f, "{}", e),
+    }
+  }
+}
+
+impl From<CustomError> for CliError {
+  fn from(e: CustomError) -> Self {
+    CliError::Asvc(e)
+  }
+}
+
+impl From<std::io::Error> for CliError {
+  fn from(e: std::io::Error) -> Self {
+    CliError::Asvc(e.into())
+  }
+}
 
 fn main() {
-    println!("Hello, world!");
+  let args: Vec<String> = std::env::args().skip(1).collect();
+  match run(&args) {
+    Ok(true) => {}
+    Ok(false) => {
+      eprintln!("invalid");
+      process::exit(1);
+    }
+    Err(e) => {
+      eprintln!("error: {}", e);
+      process::exit(2);
+    }
+  }
+}
+
+// Ok(false) only for an opening that does not verify
+fn run(args: &[String]) -> Result<bool, CliError> {
+  let (curve, args) = match args.first().map(String::as_str) {
+    Some("--curve") => (args.get(1).ok_or_else(|| usage("missing curve"))?.as_str(), &args[2..]),
+    _ => ("bls12-381", args),
+  };
+  match curve {
+    "bls12-381" => run_on::<Bls12_381>(args),
+    #[cfg(feature = "bn254")]
+    "bn254" => run_on::<ark_bn254::Bn254>(args),
+    #[cfg(feature = "bls12_377")]
+    "bls12-377" => run_on::<ark_bls12_377::Bls12_377>(args),
+    _ => Err(usage(&format!("unsupported curve {}", curve))),
+  }
+}
+
+fn run_on<E: PairingEngine>(args: &[String]) -> Result<bool, CliError> {
+  let command = args.first().ok_or_else(|| usage("missing command"))?;
+  let args = &args[1..];
+  match command.as_str() {
+    "keygen" => keygen::<E>(args),
+    "commit" => commit::<E>(args),
+    "prove" => prove::<E>(args),
+    "aggregate" => aggregate::<E>(args),
+    "update" => update::<E>(args),
+    "verify" => verify::<E>(args),
+    "help" | "--help" | "-h" => {
+      println!("{}", USAGE);
+      Ok(true)
+    }
+    _ => Err(usage(&format!("unknown command {}", command))),
+  }
+}
+
+fn keygen<E: PairingEngine>(args: &[String]) -> Result<bool, CliError> {
+  check_args(args, 2, 3)?;
+  let n: usize = parse(&args[0], "n")?;
+  let params = asvc::key_gen::<E, _>(n, &mut OsRng)?;
+  if let Some(path) = args.get(2) {
+    write(path, &params.verifying_key)?;
+  }
+  write(&args[1], &params)?;
+  Ok(true)
+}
+
+fn commit<E: PairingEngine>(args: &[String]) -> Result<bool, CliError> {
+  check_args(args, 3, 3)?;
+  let asvc = prover::<E>(&args[0])?;
+  let commitment = asvc.commit(read_vector(&args[1])?)?;
+  write(&args[2], &commitment)?;
+  Ok(true)
+}
+
+fn prove<E: PairingEngine>(args: &[String]) -> Result<bool, CliError> {
+  check_args(args, 4, 4)?;
+  let asvc = prover::<E>(&args[0])?;
+  let proof = asvc.prove_pos(read_vector(&args[1])?, parse_points(&args[2])?)?;
+  write(&args[3], &proof)?;
+  Ok(true)
+}
+
+// π_I from the single position proofs π_i, one file per position of I in order
+fn aggregate<E: PairingEngine>(args: &[String]) -> Result<bool, CliError> {
+  if args.len() < 4 {
+    return Err(usage("aggregate takes at least one proof"));
+  }
+  let asvc = Asvc::from_verifying_key(read::<Parameters<E>>(&args[0])?.verifying_key)?;
+  let points = parse_points(&args[1])?;
+  let proofs = args[3..].iter().map(|path| read::<Proof<E>>(path)).collect::<Result<Vec<_>, _>>()?;
+  let proof = asvc.aggregate_proofs(points, proofs)?;
+  write(&args[2], &proof)?;
+  Ok(true)
+}
+
+// v_i += delta, applied to a commitment or, with --proof j, to the proof of position j
+fn update<E: PairingEngine>(args: &[String]) -> Result<bool, CliError> {
+  let (args, point_j) = match args.len() {
+    5 => (args, None),
+    7 if args[5] == "--proof" => (&args[..5], Some(parse::<u32>(&args[6], "proof position")?)),
+    _ => return Err(usage("wrong number of arguments for update")),
+  };
+  let asvc = prover::<E>(&args[0])?;
+  let point: u32 = parse(&args[1], "position")?;
+  let delta = parse_scalar(&args[2])?;
+  match point_j {
+    None => {
+      let commitment = asvc.update_commit(&read::<Commitment<E>>(&args[3])?, delta, point)?;
+      write(&args[4], &commitment)?;
+    }
+    Some(point_j) => {
+      let proof = asvc.update_proof(&read::<Proof<E>>(&args[3])?, delta, point_j, point)?;
+      write(&args[4], &proof)?;
+    }
+  }
+  Ok(true)
+}
+
+fn verify<E: PairingEngine>(args: &[String]) -> Result<bool, CliError> {
+  check_args(args, 5, 5)?;
+  let bytes = fs::read(&args[0])?;
+  // a parameters file or a verifying key alone
+  let verifying_key = match Parameters::<E>::from_bytes(&bytes) {
+    Ok(params) => params.verifying_key,
+    Err(CustomError::InvalidHeader) => VerifyingKey::<E>::from_bytes(&bytes)?,
+    Err(e) => return Err(e.into()),
+  };
+  let asvc = Asvc::from_verifying_key(verifying_key)?;
+  let commitment = read::<Commitment<E>>(&args[1])?;
+  let points = parse_points(&args[2])?;
+  let values = read_vector(&args[3])?;
+  let proof = read::<Proof<E>>(&args[4])?;
+  Ok(asvc.verify_pos(&commitment, values, points, &proof)?)
+}
+
+fn usage(message: &str) -> CliError {
+  CliError::Usage(message.to_string())
+}
+
+fn check_args(args: &[String], min: usize, max: usize) -> Result<(), CliError> {
+  if args.len() < min || args.len() > max {
+    return Err(usage("wrong number of arguments"));
+  }
+  Ok(())
+}
+
+fn parse<T: FromStr>(arg: &str, name: &str) -> Result<T, CliError> {
+  arg.parse().map_err(|_| usage(&format!("invalid {}: {}", name, arg)))
+}
+
+// a decimal field element, optionally negative
+fn parse_scalar<F: PrimeField>(arg: &str) -> Result<F, CliError> {
+  let (negative, digits) = match arg.strip_prefix('-') {
+    Some(digits) => (true, digits),
+    None => (false, arg),
+  };
+  if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+    return Err(usage(&format!("invalid field element: {}", arg)));
+  }
+  let value = F::from_str(digits).map_err(|_| usage(&format!("invalid field element: {}", arg)))?;
+  Ok(if negative { -value } else { value })
+}
+
+fn parse_points(arg: &str) -> Result<Vec<u32>, CliError> {
+  arg.split(',').map(|point| parse(point.trim(), "position")).collect()
+}
+
+fn read_vector<F: PrimeField>(path: &str) -> Result<Vec<F>, CliError> {
+  fs::read_to_string(path)?
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty())
+    .map(parse_scalar)
+    .collect()
+}
+
+fn read<T: Encode>(path: &str) -> Result<T, CliError> {
+  Ok(T::from_bytes(&fs::read(path)?)?)
+}
+
+fn write<T: Encode>(path: &str, value: &T) -> Result<(), CliError> {
+  fs::write(path, value.to_bytes(Compression::Compressed)?)?;
+  Ok(())
+}
+
+fn prover<E: PairingEngine>(path: &str) -> Result<Asvc<E>, CliError> {
+  Ok(Asvc::new(read::<Parameters<E>>(path)?)?)
 }
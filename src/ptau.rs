@@ -0,0 +1,159 @@
+// Loader for snarkjs powers-of-tau files (.ptau) over BLS12-381.
+//
+// Layout, all integers little-endian:
+//   "ptau" | version u32 | #sections u32 | (type u32 | size u64 | body)*
+// Section 1 holds n8 u32, q (n8 bytes), power u32 and the ceremony power u32.
+// Section 2 holds 2^(power+1) - 1 points g^(τ^i), section 3 holds 2^power points g2^(τ^i).
+// A G1 point is x | y and a G2 point x.c0 | x.c1 | y.c0 | y.c1, every coordinate
+// n8 bytes in Montgomery form; all zeros is the point at infinity.
+use ark_bls12_381::{Bls12_381, Fq, Fq2, FqParameters, G1Affine, G2Affine};
+use ark_ff::{BigInteger384, FpParameters, Zero};
+use ark_serialize::SerializationError;
+
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::{CustomError, Parameters, Powers};
+
+const MAGIC: &[u8; 4] = b"ptau";
+const VERSION: u32 = 1;
+const HEADER: u32 = 1;
+const TAU_G1: u32 = 2;
+const TAU_G2: u32 = 3;
+// bytes of an Fq element
+const N8: usize = 48;
+
+// g^(τ^i) and g2^(τ^i) for a vector of size n, checked to be powers of one τ.
+// The G2 powers are cut at n+1 as well, or at what the file has if that's fewer.
+pub fn read_ptau<R: Read + Seek>(reader: &mut R, n: usize) -> Result<Powers<Bls12_381>, CustomError> {
+  let mut magic = [0u8; 4];
+  reader.read_exact(&mut magic)?;
+  if &magic != MAGIC || read_u32(reader)? != VERSION {
+    return Err(CustomError::InvalidHeader);
+  }
+
+  // (type, offset, size) of every section, each within the stream
+  let count = read_u32(reader)?;
+  let start = reader.stream_position()?;
+  let len = reader.seek(SeekFrom::End(0))?;
+  reader.seek(SeekFrom::Start(start))?;
+  // a section header takes 12 bytes
+  if count as u64 > len.saturating_sub(start) / 12 {
+    return Err(SerializationError::InvalidData.into());
+  }
+  let mut sections = Vec::with_capacity(count as usize);
+  for _ in 0..count {
+    let kind = read_u32(reader)?;
+    let size = read_u64(reader)?;
+    let offset = reader.stream_position()?;
+    let end = offset.checked_add(size).filter(|end| *end <= len);
+    sections.push((kind, offset, size));
+    reader.seek(SeekFrom::Start(end.ok_or(SerializationError::InvalidData)?))?;
+  }
+  let section = |kind: u32| -> Result<(u64, u64), CustomError> {
+    let mut found = sections.iter().filter(|(k, _, _)| *k == kind);
+    match (found.next(), found.next()) {
+      (Some((_, offset, size)), None) => Ok((*offset, *size)),
+      _ => Err(CustomError::InvalidHeader),
+    }
+  };
+
+  // n8 and q must be those of BLS12-381
+  let (offset, _) = section(HEADER)?;
+  reader.seek(SeekFrom::Start(offset))?;
+  if read_u32(reader)? as usize != N8 || read_fq_repr(reader)? != FqParameters::MODULUS {
+    return Err(CustomError::InvalidHeader);
+  }
+  let power = read_u32(reader)?;
+  if power >= 32 {
+    return Err(CustomError::InvalidHeader);
+  }
+  // in u64: at power 31, 2^(power+1) - 1 overflows a 32-bit usize
+  let g1_len = (1u64 << (power + 1)) - 1;
+  let g2_len = 1u64 << power;
+  if n == 0 || n as u64 >= g1_len {
+    let max = usize::try_from(g1_len - 1).unwrap_or(usize::MAX);
+    return Err(CustomError::VectorTooLong { size: n, max });
+  }
+
+  let (offset, size) = section(TAU_G1)?;
+  if size != g1_len * 2 * N8 as u64 {
+    return Err(CustomError::InvalidHeader);
+  }
+  reader.seek(SeekFrom::Start(offset))?;
+  let list_g1_tau_i = (0..n + 1).map(|_| read_g1(reader)).collect::<Result<Vec<_>, _>>()?;
+
+  let (offset, size) = section(TAU_G2)?;
+  if size != g2_len * 4 * N8 as u64 {
+    return Err(CustomError::InvalidHeader);
+  }
+  reader.seek(SeekFrom::Start(offset))?;
+  let list_g2_tau_i =
+    (0..g2_len.min(n as u64 + 1)).map(|_| read_g2(reader)).collect::<Result<Vec<_>, _>>()?;
+
+  let powers = Powers { list_g1_tau_i, list_g2_tau_i };
+  if !powers.check()? {
+    return Err(CustomError::InvalidPowers);
+  }
+  Ok(powers)
+}
+
+// Parameters for a vector of size n from a .ptau file on disk
+pub fn load_ptau<P: AsRef<Path>>(path: P, n: usize) -> Result<Parameters<Bls12_381>, CustomError> {
+  let mut reader = BufReader::new(File::open(path)?);
+  let powers = read_ptau(&mut reader, n)?;
+  Parameters::from_powers(powers.list_g1_tau_i, powers.list_g2_tau_i)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, CustomError> {
+  let mut bytes = [0u8; 4];
+  reader.read_exact(&mut bytes)?;
+  Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, CustomError> {
+  let mut bytes = [0u8; 8];
+  reader.read_exact(&mut bytes)?;
+  Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_fq_repr<R: Read>(reader: &mut R) -> Result<BigInteger384, CustomError> {
+  let mut limbs = [0u64; 6];
+  for limb in limbs.iter_mut() {
+    *limb = read_u64(reader)?;
+  }
+  Ok(BigInteger384(limbs))
+}
+
+// the Montgomery form is Fq's own representation, it only has to be reduced
+fn read_fq<R: Read>(reader: &mut R) -> Result<Fq, CustomError> {
+  let repr = read_fq_repr(reader)?;
+  if repr >= FqParameters::MODULUS {
+    return Err(SerializationError::InvalidData.into());
+  }
+  Ok(Fq::new(repr))
+}
+
+fn read_g1<R: Read>(reader: &mut R) -> Result<G1Affine, CustomError> {
+  let (x, y) = (read_fq(reader)?, read_fq(reader)?);
+  let point = G1Affine::new(x, y, x.is_zero() && y.is_zero());
+  check_point(point.infinity || (point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()))?;
+  Ok(point)
+}
+
+fn read_g2<R: Read>(reader: &mut R) -> Result<G2Affine, CustomError> {
+  let x = Fq2::new(read_fq(reader)?, read_fq(reader)?);
+  let y = Fq2::new(read_fq(reader)?, read_fq(reader)?);
+  let point = G2Affine::new(x, y, x.is_zero() && y.is_zero());
+  check_point(point.infinity || (point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()))?;
+  Ok(point)
+}
+
+fn check_point(valid: bool) -> Result<(), CustomError> {
+  if !valid {
+    return Err(SerializationError::InvalidData.into());
+  }
+  Ok(())
+}
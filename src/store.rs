@@ -0,0 +1,137 @@
+// A committed vector that keeps its commitment and proofs up to date.
+//
+// The store owns the n values, their commitment c and a cache of single position
+// proofs π_i. set(i, v) applies δ = v - v_i to c with upk_i and to every cached π_j
+// with upk_i, upk_j, O(1) group operations each, so neither is ever recomputed.
+// A subvector proof aggregates the cached π_i, computing and caching missing ones first.
+use ark_ec::PairingEngine;
+use ark_ff::Zero;
+
+use ark_std::collections::BTreeMap;
+use ark_std::{vec, vec::Vec};
+
+use crate::{check_points, Asvc, Commitment, CustomError, Parameters, Proof};
+
+pub struct VectorStore<E: PairingEngine> {
+  asvc: Asvc<E>,
+  values: Vec<E::Fr>,
+  commitment: Commitment<E>,
+  // π_i by position
+  proofs: BTreeMap<u32, Proof<E>>,
+}
+
+impl<E: PairingEngine> VectorStore<E> {
+  // Commits to values, at most n of them; positions past the end hold 0.
+  // The context needs a proving key.
+  pub fn new(asvc: Asvc<E>, mut values: Vec<E::Fr>) -> Result<Self, CustomError> {
+    let commitment = asvc.commit(values.clone())?;
+    values.resize(asvc.size(), E::Fr::zero());
+    Ok(VectorStore { asvc, values, commitment, proofs: BTreeMap::new() })
+  }
+
+  pub fn from_parameters(params: Parameters<E>, values: Vec<E::Fr>) -> Result<Self, CustomError> {
+    Self::new(Asvc::new(params)?, values)
+  }
+
+  pub fn context(&self) -> &Asvc<E> {
+    &self.asvc
+  }
+
+  // n, the domain size
+  pub fn len(&self) -> usize {
+    self.values.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+
+  pub fn values(&self) -> &[E::Fr] {
+    &self.values
+  }
+
+  pub fn get(&self, point: u32) -> Option<E::Fr> {
+    self.values.get(point as usize).copied()
+  }
+
+  pub fn commitment(&self) -> &Commitment<E> {
+    &self.commitment
+  }
+
+  // positions with a cached proof, in increasing order
+  pub fn cached_points(&self) -> Vec<u32> {
+    self.proofs.keys().copied().collect()
+  }
+
+  // v_i = value; updates c and every cached proof
+  pub fn set(&mut self, point: u32, value: E::Fr) -> Result<(), CustomError> {
+    check_points(&[point], self.len())?;
+    let delta = value - self.values[point as usize];
+    if delta.is_zero() {
+      return Ok(());
+    }
+    let commitment = self.asvc.update_commit(&self.commitment, delta, point)?;
+
+    let mut proofs = BTreeMap::new();
+    for (point_j, proof) in self.proofs.iter() {
+      proofs.insert(*point_j, self.asvc.update_proof(proof, delta, *point_j, point)?);
+    }
+
+    // nothing changes unless every update succeeded
+    self.values[point as usize] = value;
+    self.commitment = commitment;
+    self.proofs = proofs;
+    Ok(())
+  }
+
+  // caches π_i for the given positions
+  pub fn cache(&mut self, points: &[u32]) -> Result<(), CustomError> {
+    check_points(points, self.len())?;
+    for point in points.iter() {
+      self.proof(*point)?;
+    }
+    Ok(())
+  }
+
+  // caches π_i for every position, with prove_all
+  pub fn cache_all(&mut self) -> Result<(), CustomError> {
+    let proofs = self.asvc.prove_all(self.values.clone())?;
+    self.proofs = proofs.into_iter().enumerate().map(|(i, proof)| (i as u32, proof)).collect();
+    Ok(())
+  }
+
+  // drops the cached proofs, which set then no longer has to update
+  pub fn clear_cache(&mut self) {
+    self.proofs.clear();
+  }
+
+  // π_i, from the cache or computed and cached
+  pub fn proof(&mut self, point: u32) -> Result<Proof<E>, CustomError> {
+    check_points(&[point], self.len())?;
+    if let Some(proof) = self.proofs.get(&point) {
+      return Ok(proof.clone());
+    }
+    let proof = self.asvc.prove_pos(self.values.clone(), vec![point])?;
+    self.proofs.insert(point, proof.clone());
+    Ok(proof)
+  }
+
+  // π_I aggregated from the π_i, i ∈ I, with v_I
+  pub fn prove(&mut self, points: Vec<u32>) -> Result<(Vec<E::Fr>, Proof<E>), CustomError> {
+    self.cache(&points)?;
+    let proofs = points.iter().map(|point| self.proofs[point].clone()).collect();
+    let point_values = points.iter().map(|point| self.values[*point as usize]).collect();
+    let proof = self.asvc.aggregate_proofs(points, proofs)?;
+    Ok((point_values, proof))
+  }
+
+  // checks v_I, π_I against the current commitment
+  pub fn verify(
+    &self,
+    point_values: Vec<E::Fr>,
+    points: Vec<u32>,
+    proof: &Proof<E>,
+  ) -> Result<bool, CustomError> {
+    self.asvc.verify_pos(&self.commitment, point_values, points, proof)
+  }
+}
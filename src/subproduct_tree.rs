@@ -0,0 +1,183 @@
+// Subproduct tree over points x_0 .. x_(m-1)
+// - leaves are (x - x_i), every inner node is the product of its two children,
+//   so the root is the vanishing polynomial A(x) = ∏(x - x_i)
+// - going down the tree with remainders evaluates a polynomial at every x_i
+// - going up the tree with cross products interpolates
+// Every operation costs O(m log^2 m) with FFT multiplication and Newton division.
+use ark_ff::{batch_inversion, FftField, Zero};
+use ark_poly::univariate::{DenseOrSparsePolynomial, DensePolynomial};
+use ark_poly::{Polynomial, UVPolynomial};
+use ark_std::{vec, vec::Vec};
+
+// below this many coefficients schoolbook multiplication / long division win over FFTs
+const NAIVE_THRESHOLD: usize = 64;
+
+pub(crate) struct SubproductTree<F: FftField> {
+  points: Vec<F>,
+  // layers[0] holds the leaves, the last layer holds only the root.
+  // Node k of layer l has children 2k and 2k+1 in layer l-1; an odd node out
+  // at the end of a layer is carried up unchanged.
+  layers: Vec<Vec<DensePolynomial<F>>>,
+}
+
+impl<F: FftField> SubproductTree<F> {
+  // points must be non-empty
+  pub(crate) fn new(points: &[F]) -> Self {
+    let leaves: Vec<DensePolynomial<F>> = points
+      .iter()
+      .map(|x| DensePolynomial::from_coefficients_vec(vec![-*x, F::one()]))
+      .collect();
+
+    let mut layers = vec![leaves];
+    while layers[layers.len() - 1].len() > 1 {
+      let below = &layers[layers.len() - 1];
+      let layer = below
+        .chunks(2)
+        .map(|pair| match pair {
+          [left, right] => mul(left, right),
+          [single] => single.clone(),
+          _ => unreachable!(),
+        })
+        .collect();
+      layers.push(layer);
+    }
+
+    SubproductTree { points: points.to_vec(), layers }
+  }
+
+  // A(x) = ∏(x - x_i)
+  pub(crate) fn vanishing_polynomial(&self) -> &DensePolynomial<F> {
+    &self.layers.last().unwrap()[0]
+  }
+
+  // f(x_0), .., f(x_(m-1))
+  pub(crate) fn evaluate(&self, f: &DensePolynomial<F>) -> Vec<F> {
+    let top = self.layers.len() - 1;
+    let mut remainders = vec![divide(f, self.vanishing_polynomial()).1];
+
+    // the leaves are linear, so stop one layer above them and evaluate directly
+    for layer in self.layers[..top].iter().skip(1).rev() {
+      remainders = layer
+        .iter()
+        .enumerate()
+        .map(|(k, node)| divide(&remainders[k / 2], node).1)
+        .collect();
+    }
+
+    self
+      .points
+      .iter()
+      .enumerate()
+      .map(|(i, x)| {
+        if top == 0 { f.evaluate(x) } else { remainders[i / 2].evaluate(x) }
+      })
+      .collect()
+  }
+
+  // A'(x_0), .., A'(x_(m-1)); A'(x_i) = ∏_{j≠i}(x_i - x_j)
+  pub(crate) fn derivative_evaluations(&self) -> Vec<F> {
+    let a = self.vanishing_polynomial();
+    let derivative = DensePolynomial::from_coefficients_vec(
+      a.coeffs
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(k, c)| F::from(k as u64) * c)
+        .collect(),
+    );
+    self.evaluate(&derivative)
+  }
+
+  // ∑ c_i·A(x)/(x - x_i)
+  pub(crate) fn linear_combination(&self, c: &[F]) -> DensePolynomial<F> {
+    let mut sums: Vec<DensePolynomial<F>> = c
+      .iter()
+      .map(|c_i| DensePolynomial::from_coefficients_vec(vec![*c_i]))
+      .collect();
+
+    // sum(parent) = sum(left)·right + sum(right)·left
+    for layer in self.layers[..self.layers.len() - 1].iter() {
+      sums = sums
+        .chunks(2)
+        .zip(layer.chunks(2))
+        .map(|(s, nodes)| match (s, nodes) {
+          ([s_left, s_right], [left, right]) => &mul(s_left, right) + &mul(s_right, left),
+          ([s_single], [_]) => s_single.clone(),
+          _ => unreachable!(),
+        })
+        .collect();
+    }
+    sums.pop().unwrap()
+  }
+
+  // R(x) of degree < m with R(x_i) = v_i, or None if two points coincide
+  pub(crate) fn interpolate(&self, values: &[F]) -> Option<DensePolynomial<F>> {
+    let mut c = self.derivative_evaluations();
+    if c.iter().any(|c_i| c_i.is_zero()) {
+      return None;
+    }
+    batch_inversion(&mut c);
+    c.iter_mut().zip(values.iter()).for_each(|(c_i, v_i)| *c_i *= v_i);
+    Some(self.linear_combination(&c))
+  }
+}
+
+fn mul<F: FftField>(a: &DensePolynomial<F>, b: &DensePolynomial<F>) -> DensePolynomial<F> {
+  if a.coeffs.len().min(b.coeffs.len()) <= NAIVE_THRESHOLD {
+    a.naive_mul(b)
+  } else {
+    a * b
+  }
+}
+
+// a(x) mod x^k
+fn truncate<F: FftField>(a: &DensePolynomial<F>, k: usize) -> DensePolynomial<F> {
+  DensePolynomial::from_coefficients_slice(&a.coeffs[..a.coeffs.len().min(k)])
+}
+
+// 1/h(x) mod x^k by Newton iteration: g ← g·(2 - h·g) mod x^(2·len)
+fn inverse_mod_xk<F: FftField>(h: &DensePolynomial<F>, k: usize) -> DensePolynomial<F> {
+  let mut g = DensePolynomial::from_coefficients_vec(vec![h.coeffs[0].inverse().unwrap()]);
+  let mut len = 1;
+  while len < k {
+    len = (2 * len).min(k);
+    let mut t = -truncate(&mul(&truncate(h, len), &g), len);
+    if t.coeffs.is_empty() {
+      t.coeffs.push(F::zero());
+    }
+    t.coeffs[0] += F::from(2u64);
+    g = truncate(&mul(&g, &t), len);
+  }
+  g
+}
+
+fn reverse<F: FftField>(a: &DensePolynomial<F>, len: usize) -> DensePolynomial<F> {
+  let mut coeffs = a.coeffs.clone();
+  coeffs.resize(len, F::zero());
+  coeffs.reverse();
+  DensePolynomial::from_coefficients_vec(coeffs)
+}
+
+// (q, r) with a = q·b + r, deg r < deg b; b must be non-zero
+pub(crate) fn divide<F: FftField>(
+  a: &DensePolynomial<F>,
+  b: &DensePolynomial<F>,
+) -> (DensePolynomial<F>, DensePolynomial<F>) {
+  if a.is_zero() || a.degree() < b.degree() {
+    return (DensePolynomial::zero(), a.clone());
+  }
+  let (m, k) = (a.degree(), b.degree());
+  if k < NAIVE_THRESHOLD || m - k < NAIVE_THRESHOLD {
+    let a: DenseOrSparsePolynomial<'_, F> = a.into();
+    let b: DenseOrSparsePolynomial<'_, F> = b.into();
+    return a.divide_with_q_and_r(&b).unwrap();
+  }
+
+  // rev(q) = rev(a)/rev(b) mod x^(m-k+1); rev(b) has constant term lead(b) ≠ 0
+  let quotient_len = m - k + 1;
+  let rev_b_inv = inverse_mod_xk(&truncate(&reverse(b, k + 1), quotient_len), quotient_len);
+  let rev_q = truncate(&mul(&truncate(&reverse(a, m + 1), quotient_len), &rev_b_inv), quotient_len);
+  let q = reverse(&rev_q, quotient_len);
+  let r = a - &mul(&q, b);
+  (q, r)
+}
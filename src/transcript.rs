@@ -0,0 +1,100 @@
+// Fiat–Shamir transcript.
+//
+// A running Blake2b state, started from a protocol label. Every message is absorbed as
+//   op | len(label) | label | len(message) | message
+// with op = 0, lengths as u64 little-endian and values in their compressed
+// ark-serialize encoding. A challenge absorbs op = 1 and its label, is read from
+// the hash of the state so far, and is absorbed back, so later challenges depend on it.
+//
+// Transcript also implements RngCore, for APIs that take an rng. That is only sound
+// once the transcript has absorbed everything the randomness must not be chosen before.
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::{Error, RngCore};
+use ark_std::vec::Vec;
+use blake2::{Blake2b, Digest};
+
+use crate::CustomError;
+
+const ABSORB: u8 = 0;
+const SQUEEZE: u8 = 1;
+
+#[derive(Clone)]
+pub struct Transcript {
+  hasher: Blake2b,
+}
+
+impl Transcript {
+  // a transcript for one protocol; the label separates it from every other one
+  pub fn new(label: &'static [u8]) -> Self {
+    let mut transcript = Transcript { hasher: Blake2b::new() };
+    transcript.append_message(b"asvc-transcript", label);
+    transcript
+  }
+
+  pub fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+    self.absorb(ABSORB, label);
+    self.hasher.update((message.len() as u64).to_le_bytes());
+    self.hasher.update(message);
+  }
+
+  // a Commitment, a Proof, a field element, a list of positions, ...
+  pub fn append<T: CanonicalSerialize>(
+    &mut self,
+    label: &'static [u8],
+    value: &T,
+  ) -> Result<(), CustomError> {
+    let mut message = Vec::with_capacity(value.serialized_size());
+    value.serialize(&mut message)?;
+    self.append_message(label, &message);
+    Ok(())
+  }
+
+  pub fn challenge_scalar<F: PrimeField>(&mut self, label: &'static [u8]) -> F {
+    F::from_le_bytes_mod_order(&self.challenge_bytes(label))
+  }
+
+  pub fn challenge_scalars<F: PrimeField>(&mut self, label: &'static [u8], count: usize) -> Vec<F> {
+    (0..count).map(|_| self.challenge_scalar(label)).collect()
+  }
+
+  // 64 bytes, absorbed back into the state
+  fn challenge_bytes(&mut self, label: &'static [u8]) -> Vec<u8> {
+    self.absorb(SQUEEZE, label);
+    let output = self.hasher.clone().finalize().to_vec();
+    self.hasher.update(&output);
+    output
+  }
+
+  fn absorb(&mut self, op: u8, label: &[u8]) {
+    self.hasher.update([op]);
+    self.hasher.update((label.len() as u64).to_le_bytes());
+    self.hasher.update(label);
+  }
+}
+
+impl RngCore for Transcript {
+  fn next_u32(&mut self) -> u32 {
+    let mut bytes = [0u8; 4];
+    self.fill_bytes(&mut bytes);
+    u32::from_le_bytes(bytes)
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    let mut bytes = [0u8; 8];
+    self.fill_bytes(&mut bytes);
+    u64::from_le_bytes(bytes)
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    for chunk in dest.chunks_mut(64) {
+      let output = self.challenge_bytes(b"rng");
+      chunk.copy_from_slice(&output[..chunk.len()]);
+    }
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+    self.fill_bytes(dest);
+    Ok(())
+  }
+}
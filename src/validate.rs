@@ -0,0 +1,186 @@
+// Validation of parameters received from someone else.
+//
+// Every check is one randomized equation: a wrong key passes it only with
+// probability about n/|Fr|. The random ρ are drawn from a transcript that has
+// absorbed the keys being checked, so they cannot be chosen to match.
+// - Powers: g^(τ^i), g2^(τ^i) are powers of one τ ≠ 0, by the chain checks of Powers::check
+// - A: a = g^(τ^n - 1), and a ≠ 1: with τ^n = 1, τ is a root of unity anyone can find
+// - LagrangeBases: l_i = g^(L_i(τ)). With P(x) = ∑ ρ_j x^j of degree < n,
+//   P(τ) = ∑_i P(ω^i) L_i(τ), so ∑_i P(ω^i) l_i = ∑_j ρ_j g^(τ^j), one scalar FFT and two MSMs
+// - UpdateKeys: every upk_i, by verify_all_upks
+// - HidingKey: h^(τ^i) are powers of the same τ, and h^(L_i(τ)) their Lagrange bases
+// - Mismatch: the proving and verifying key come from different setups
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::Zero;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+
+use ark_std::fmt;
+use ark_std::ops::Sub;
+
+use crate::ceremony::{check_g1_powers, check_g2_powers, linear_combination};
+use crate::{
+  group_gen, new_domain, verify_all_upks_with, CustomError, Parameters, ProvingKey, Roots, Transcript,
+  VerifyingKey,
+};
+
+// the check a key fails
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParameterCheck {
+  Powers,
+  A,
+  LagrangeBases,
+  UpdateKeys,
+  HidingKey,
+  Mismatch,
+}
+
+impl fmt::Display for ParameterCheck {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ParameterCheck::Powers => write!(f, "g^(τ^i), g2^(τ^i) are not powers of one τ"),
+      ParameterCheck::A => write!(f, "a is not g^(τ^n - 1)"),
+      ParameterCheck::LagrangeBases => write!(f, "l_i are not the Lagrange bases of the powers"),
+      ParameterCheck::UpdateKeys => write!(f, "invalid update keys"),
+      ParameterCheck::HidingKey => write!(f, "invalid hiding key"),
+      ParameterCheck::Mismatch => write!(f, "proving and verifying key do not belong together"),
+    }
+  }
+}
+
+fn ensure(ok: bool, check: ParameterCheck) -> Result<(), CustomError> {
+  if !ok {
+    return Err(CustomError::InvalidParameters(check));
+  }
+  Ok(())
+}
+
+// n and its domain; n must be a domain size
+fn key_domain<E: PairingEngine>(n: usize) -> Result<GeneralEvaluationDomain<E::Fr>, CustomError> {
+  let domain: GeneralEvaluationDomain<E::Fr> = new_domain(n)?;
+  if domain.size() != n {
+    return Err(CustomError::UnsupportedDomainSize(n));
+  }
+  Ok(domain)
+}
+
+// bases_i = P^(L_i(τ)), i ∈ [0, n) for powers_j = P^(τ^j):
+//   ∑_i P(ω^i) bases_i = ∑_j ρ_j powers_j
+fn check_lagrange_bases<E: PairingEngine>(
+  powers: &[E::G1Affine],
+  bases: &[E::G1Affine],
+  domain: &GeneralEvaluationDomain<E::Fr>,
+  rho: &[E::Fr],
+) -> bool {
+  let evaluations = domain.fft(rho);
+  linear_combination(bases, &evaluations) == linear_combination(&powers[..bases.len()], rho)
+}
+
+impl<E: PairingEngine> VerifyingKey<E> {
+  // Checks that the powers in G1, G2 and of h share one τ, and a against g2^(τ^n)
+  // when the key keeps it. A trimmed key does not, and only ProvingKey::validate
+  // checks its a.
+  pub fn validate(&self) -> Result<(), CustomError> {
+    let (g1, g2) = (&self.list_g1_tau_i, &self.list_g2_tau_i);
+    // with g alone in G1 the G2 powers past g2^τ cannot be checked
+    if g1.is_empty() || g2.len() < 2 || (g1.len() < 2 && g2.len() > 2) {
+      return Err(CustomError::MalformedKey);
+    }
+    if self.list_h_tau_i.as_ref().is_some_and(|h| h.len() != g1.len()) {
+      return Err(CustomError::MalformedKey);
+    }
+    let n = self.size()?;
+    key_domain::<E>(n)?;
+
+    let mut transcript = Transcript::new(b"asvc-validate-verifying-key");
+    transcript.append(b"verifying-key", self)?;
+    let rho = transcript.challenge_scalars::<E::Fr>(b"powers", g1.len().max(g2.len()));
+
+    ensure(!g1[0].is_zero() && !g2[0].is_zero() && !g2[1].is_zero(), ParameterCheck::Powers)?;
+    ensure(check_g1_powers::<E>(g1, &g2[0], &g2[1], &rho), ParameterCheck::Powers)?;
+    if g1.len() >= 2 {
+      ensure(check_g2_powers::<E>(&g1[0], &g1[1], g2, &rho), ParameterCheck::Powers)?;
+    }
+
+    // e(a, g2) = e(g, g2^(τ^n)/g2)
+    ensure(!self.a.is_zero(), ParameterCheck::A)?;
+    if g2.len() > n {
+      let g2_a = g2[n].into_projective().sub(&g2[0].into_projective()).into_affine();
+      ensure(E::pairing(self.a, g2[0]) == E::pairing(g1[0], g2_a), ParameterCheck::A)?;
+    }
+
+    if let Some(h) = &self.list_h_tau_i {
+      ensure(!h[0].is_zero(), ParameterCheck::HidingKey)?;
+      ensure(check_g1_powers::<E>(h, &g2[0], &g2[1], &rho), ParameterCheck::HidingKey)?;
+    }
+    Ok(())
+  }
+}
+
+impl<E: PairingEngine> ProvingKey<E> {
+  // Checks the proving key against a verifying key that already passed validate:
+  // the powers, a, l_i, every upk_i and the hiding key, and that both keys share them.
+  pub fn validate(&self, vrk_params: &VerifyingKey<E>) -> Result<(), CustomError> {
+    let n = vrk_params.size()?;
+    let g1 = &self.list_g1_tau_i;
+    if g1.len() != n + 1
+      || self.list_l_i.len() != n
+      || self.list_update_keys.len() != n
+      || vrk_params.list_g1_tau_i.is_empty()
+      || vrk_params.list_g2_tau_i.len() < 2
+    {
+      return Err(CustomError::MalformedKey);
+    }
+    if let Some(key) = &self.hiding_key {
+      if key.list_h_tau_i.len() != n + 1 || key.list_h_l_i.len() != n {
+        return Err(CustomError::MalformedKey);
+      }
+    }
+    let domain = key_domain::<E>(n)?;
+
+    // the verifying key keeps a prefix of the same powers
+    let shared = vrk_params.list_g1_tau_i.len();
+    ensure(shared <= n + 1 && g1[..shared] == vrk_params.list_g1_tau_i[..], ParameterCheck::Mismatch)?;
+    let hiding = match (&self.hiding_key, &vrk_params.list_h_tau_i) {
+      (Some(key), Some(h)) => h.len() <= n + 1 && key.list_h_tau_i[..h.len()] == h[..],
+      (None, None) => true,
+      _ => false,
+    };
+    ensure(hiding, ParameterCheck::Mismatch)?;
+
+    let mut transcript = Transcript::new(b"asvc-validate-proving-key");
+    transcript.append(b"verifying-key", vrk_params)?;
+    transcript.append(b"proving-key", self)?;
+    let (g2, g2_tau) = (&vrk_params.list_g2_tau_i[0], &vrk_params.list_g2_tau_i[1]);
+
+    let rho = transcript.challenge_scalars::<E::Fr>(b"powers", n);
+    ensure(check_g1_powers::<E>(g1, g2, g2_tau, &rho), ParameterCheck::Powers)?;
+
+    let a = g1[n].into_projective().sub(&g1[0].into_projective());
+    ensure(!a.is_zero() && vrk_params.a.into_projective() == a, ParameterCheck::A)?;
+
+    let rho = transcript.challenge_scalars::<E::Fr>(b"lagrange-bases", n);
+    ensure(check_lagrange_bases::<E>(g1, &self.list_l_i, &domain, &rho), ParameterCheck::LagrangeBases)?;
+
+    let roots = Roots::Omega(group_gen::<E>(&domain));
+    let upks = verify_all_upks_with(vrk_params, self, roots, &mut transcript)?;
+    ensure(upks, ParameterCheck::UpdateKeys)?;
+
+    if let Some(key) = &self.hiding_key {
+      let h = &key.list_h_tau_i;
+      let rho = transcript.challenge_scalars::<E::Fr>(b"hiding-key", n);
+      ensure(!h[0].is_zero(), ParameterCheck::HidingKey)?;
+      ensure(check_g1_powers::<E>(h, g2, g2_tau, &rho), ParameterCheck::HidingKey)?;
+      ensure(check_lagrange_bases::<E>(h, &key.list_h_l_i, &domain, &rho), ParameterCheck::HidingKey)?;
+    }
+    Ok(())
+  }
+}
+
+impl<E: PairingEngine> Parameters<E> {
+  // VerifyingKey::validate, then ProvingKey::validate against it;
+  // InvalidParameters names the first check that fails
+  pub fn validate(&self) -> Result<(), CustomError> {
+    self.verifying_key.validate()?;
+    self.proving_key.validate(&self.verifying_key)
+  }
+}
@@ -1,12 +1,11 @@
-use ark_ec::group;
 // GeneralEvaluationDomain
   // Defines a domain over which finite field (I)FFTs can be performed. 
   // Generally tries to build a radix-2 domain and falls back to a mixed-radix domain 
   // if the radix-2 multiplicative subgroup is too small.
 use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
-use ark_bls12_381::{Bls12_381 as E, Fr};
+use ark_ec::PairingEngine;
 use ark_std::test_rng;
-use ark_ff::UniformRand;
+use ark_ff::{FftField, UniformRand};
 use asvc::{aggregate_proofs, verify_pos};
 use std::time::Instant;
 
@@ -14,35 +13,69 @@ use core::ops::Add;
 
 extern crate asvc;
 
-fn group_gen(domain: &GeneralEvaluationDomain<Fr>) -> Fr {
+// every test below that takes E runs once per enabled curve, as <curve>::test_*
+macro_rules! curve_tests {
+  ($curve:ident, $E:ty, [$($test:ident),*]) => {
+    mod $curve {
+      $(
+        #[test]
+        fn $test() {
+          super::$test::<$E>();
+        }
+      )*
+    }
+  };
+  ($curve:ident, $E:ty) => {
+    curve_tests!($curve, $E, [
+      test_aggregatable_svc, test_encoding, test_errors, test_prove_all, test_large_subvector,
+      test_batch_verify, test_cross_commitment_aggregation, test_context, test_ceremony,
+      test_lagrange_keys, test_update_keys, test_key_gen_progress, test_trim,
+      test_update_subvector_proof, test_update_commit_batch, test_verify_all_upks, test_hiding,
+      test_linear, test_byte_blobs, test_transcript, test_validate, test_vector_store
+    ]);
+  };
+}
+
+curve_tests!(bls12_381, ark_bls12_381::Bls12_381);
+#[cfg(feature = "bn254")]
+curve_tests!(bn254, ark_bn254::Bn254);
+#[cfg(feature = "bls12_377")]
+curve_tests!(bls12_377, ark_bls12_377::Bls12_377);
+
+fn group_gen<F: FftField>(domain: &GeneralEvaluationDomain<F>) -> F {
   match domain {
     GeneralEvaluationDomain::Radix2(radix) => radix.group_gen,
     GeneralEvaluationDomain::MixedRadix(mixed) => mixed.group_gen,
   }
 }
-#[test]
-fn test_aggregatable_svc(){
+// g^(τ^i), g2^(τ^i), i ∈ [0, n] for a known τ
+fn powers_of<E: PairingEngine>(tau: E::Fr, n: usize) -> (Vec<E::G1Affine>, Vec<E::G2Affine>) {
+  use ark_ec::{AffineCurve, ProjectiveCurve};
+  let mut tau_i = E::Fr::from(1u64);
+  let (mut g1, mut g2) = (Vec::new(), Vec::new());
+  for _ in 0..=n {
+    g1.push(E::G1Affine::prime_subgroup_generator().mul(tau_i).into_affine());
+    g2.push(E::G2Affine::prime_subgroup_generator().mul(tau_i).into_affine());
+    tau_i *= tau;
+  }
+  (g1, g2)
+}
+
+fn test_aggregatable_svc<E: PairingEngine>(){
   let rng = &mut test_rng();
   let size: usize = 8;
   let params = asvc::key_gen::<E,_>(size, rng).unwrap();
 
-  let domain: GeneralEvaluationDomain<Fr> = EvaluationDomain::<Fr>::new(size).unwrap();
+  let domain: GeneralEvaluationDomain<E::Fr> = EvaluationDomain::<E::Fr>::new(size).unwrap();
 
-  let mut values = Vec::<Fr>::new();
-  values.push(Fr::rand(rng)); // ark_ff::UniformRand
-  values.push(Fr::rand(rng));
-  values.push(Fr::rand(rng));
-  values.push(Fr::rand(rng));
-  values.push(Fr::rand(rng));
-  values.push(Fr::rand(rng));
-  values.push(Fr::rand(rng));
-  values.push(Fr::rand(rng)); // 8
+  // ark_ff::UniformRand
+  let values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect(); // 8
 
   let c = asvc::commit(&params.proving_key, values.clone()).unwrap();
 
   let start = Instant::now();
   let mut points = Vec::<u32>::new();
-  let mut point_values = Vec::<Fr>::new();
+  let mut point_values = Vec::<E::Fr>::new();
 
   // 0, 1, 5
   points.push(0);
@@ -76,7 +109,7 @@ fn test_aggregatable_svc(){
 
   let start = Instant::now();
   let index: u32 = 3;
-  let delta = Fr::rand(rng);
+  let delta = E::Fr::rand(rng);
 
   let points_i = vec![index];
   let point_values_i = vec![values[index as usize].add(&delta)];  // core::ops::Add;
@@ -164,4 +197,1162 @@ fn test_aggregatable_svc(){
   println!("Asvc verify aggregate proofs Time: {:?}", total_setup);
   assert!(rs);
 
-}
\ No newline at end of file
+}
+fn test_encoding<E: PairingEngine>(){
+  use asvc::{Commitment, Compression, CustomError, Encode, Parameters, Proof, FORMAT_VERSION};
+
+  let rng = &mut test_rng();
+  let size: usize = 8;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+  let c = asvc::commit(&params.proving_key, values.clone()).unwrap();
+  let proof = asvc::prove_pos(&params.proving_key, values, vec![1, 5]).unwrap();
+
+  for compression in [Compression::Compressed, Compression::Uncompressed] {
+    let bytes = params.to_bytes(compression).unwrap();
+    assert_eq!(bytes[0], FORMAT_VERSION);
+    assert!(Parameters::<E>::from_bytes(&bytes).unwrap() == params);
+
+    let bytes = c.to_bytes(compression).unwrap();
+    assert!(Commitment::<E>::from_bytes(&bytes).unwrap() == c);
+    // same body, different type tag
+    assert!(matches!(Proof::<E>::from_bytes(&bytes), Err(CustomError::InvalidHeader)));
+
+    let bytes = proof.to_bytes(compression).unwrap();
+    assert!(Proof::<E>::from_bytes(&bytes).unwrap() == proof);
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(Proof::<E>::from_bytes(&trailing).is_err());
+  }
+
+  let mut bytes = c.to_bytes(Compression::Compressed).unwrap();
+  bytes[0] = FORMAT_VERSION + 1;
+  assert!(matches!(Commitment::<E>::from_bytes(&bytes),
+                   Err(CustomError::UnsupportedFormatVersion(_))));
+
+  // tamper with the low byte of x: the point leaves the curve
+  let mut bytes = c.to_bytes(Compression::Uncompressed).unwrap();
+  bytes[3] ^= 1;
+  assert!(Commitment::<E>::from_bytes(&bytes).is_err());
+}
+
+fn test_errors<E: PairingEngine>(){
+  use asvc::CustomError;
+
+  let rng = &mut test_rng();
+  let size: usize = 8;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let domain: GeneralEvaluationDomain<E::Fr> = EvaluationDomain::<E::Fr>::new(size).unwrap();
+  let omega = group_gen(&domain);
+  let values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+  let c = asvc::commit(&params.proving_key, values.clone()).unwrap();
+  let proof = asvc::prove_pos(&params.proving_key, values.clone(), vec![0, 1]).unwrap();
+
+  assert!(matches!(asvc::key_gen::<E,_>(0, rng), Err(CustomError::UnsupportedDomainSize(0))));
+  assert!(matches!(asvc::commit(&params.proving_key, vec![]), Err(CustomError::EmptyVector)));
+  assert!(matches!(asvc::commit(&params.proving_key, vec![E::Fr::rand(rng); size + 1]),
+                   Err(CustomError::VectorTooLong { size: 9, max: 8 })));
+
+  assert!(matches!(asvc::prove_pos(&params.proving_key, values.clone(), vec![8]),
+                   Err(CustomError::IndexOutOfRange { index: 8, size: 8 })));
+  assert!(matches!(asvc::prove_pos(&params.proving_key, values.clone(), vec![1, 1]),
+                   Err(CustomError::DuplicateIndex(1))));
+  assert!(matches!(asvc::prove_pos(&params.proving_key, values.clone(), vec![]),
+                   Err(CustomError::EmptyIndexSet)));
+
+  assert!(matches!(asvc::verify_pos(&params.verifying_key, &c, vec![values[0]], vec![0, 1], &proof, omega),
+                   Err(CustomError::LengthMismatch { expected: 2, found: 1 })));
+
+  let mut short_vrk = params.verifying_key.clone();
+  short_vrk.list_g2_tau_i.truncate(2);
+  assert!(matches!(asvc::verify_pos(&short_vrk, &c, vec![values[0], values[1]], vec![0, 1], &proof, omega),
+                   Err(CustomError::SubvectorTooLarge { size: 2, max: 1 })));
+
+  let upk = &params.proving_key.list_update_keys[0];
+  assert!(matches!(asvc::verify_upk(&params.verifying_key, 8, upk, omega),
+                   Err(CustomError::IndexOutOfRange { .. })));
+  assert!(matches!(asvc::update_commit(&c, E::Fr::rand(rng), 9, upk, omega, size),
+                   Err(CustomError::IndexOutOfRange { .. })));
+  assert!(matches!(asvc::aggregate_proofs(vec![1, 1], vec![proof.clone(), proof.clone()], omega),
+                   Err(CustomError::DuplicateIndex(1))));
+  assert!(matches!(asvc::aggregate_proofs(vec![1], vec![proof.clone(), proof], omega),
+                   Err(CustomError::LengthMismatch { expected: 1, found: 2 })));
+}
+
+fn test_prove_all<E: PairingEngine>(){
+  let rng = &mut test_rng();
+  let size: usize = 8;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let domain: GeneralEvaluationDomain<E::Fr> = EvaluationDomain::<E::Fr>::new(size).unwrap();
+  let values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+  let c = asvc::commit(&params.proving_key, values.clone()).unwrap();
+
+  let start = Instant::now();
+  let proofs = asvc::prove_all(&params.proving_key, values.clone()).unwrap();
+  println!("ASVC Prove All Time: {:?}", start.elapsed());
+  assert_eq!(proofs.len(), size);
+
+  for (i, proof) in proofs.iter().enumerate() {
+    let expected = asvc::prove_pos(&params.proving_key, values.clone(), vec![i as u32]).unwrap();
+    assert!(*proof == expected);
+  }
+  let rs = asvc::verify_pos(&params.verifying_key, &c, vec![values[6]], vec![6],
+                            &proofs[6], group_gen(&domain))
+                            .unwrap();
+  assert!(rs);
+
+  // a shorter vector is zero-padded like in commit
+  let proofs = asvc::prove_all(&params.proving_key, values[..3].to_vec()).unwrap();
+  let expected = asvc::prove_pos(&params.proving_key, values[..3].to_vec(), vec![7]).unwrap();
+  assert!(proofs[7] == expected);
+}
+
+fn test_large_subvector<E: PairingEngine>(){
+  // large enough for the FFT / Newton division paths of the subproduct tree
+  let rng = &mut test_rng();
+  let size: usize = 256;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let domain: GeneralEvaluationDomain<E::Fr> = EvaluationDomain::<E::Fr>::new(size).unwrap();
+  let values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+  let c = asvc::commit(&params.proving_key, values.clone()).unwrap();
+
+  let points: Vec<u32> = (0..size as u32).filter(|i| i % 3 != 1).collect();
+  let point_values: Vec<E::Fr> = points.iter().map(|i| values[*i as usize]).collect();
+
+  let start = Instant::now();
+  let proof = asvc::prove_pos(&params.proving_key, values.clone(), points.clone()).unwrap();
+  let rs = asvc::verify_pos(&params.verifying_key, &c, point_values.clone(), points.clone(),
+                            &proof, group_gen(&domain))
+                            .unwrap();
+  println!("ASVC Prove/Verify |I| = {} Time: {:?}", points.len(), start.elapsed());
+  assert!(rs);
+
+  let mut wrong_values = point_values.clone();
+  wrong_values[100] = wrong_values[100].add(&E::Fr::from(1u64));
+  let rs = asvc::verify_pos(&params.verifying_key, &c, wrong_values, points.clone(),
+                            &proof, group_gen(&domain))
+                            .unwrap();
+  assert!(!rs);
+
+  let proofs = asvc::prove_all(&params.proving_key, values).unwrap();
+  let point_proofs: Vec<_> = points.iter().map(|i| proofs[*i as usize].clone()).collect();
+  let aggregated = asvc::aggregate_proofs(points.clone(), point_proofs, group_gen(&domain)).unwrap();
+  assert!(aggregated == proof);
+}
+
+fn test_batch_verify<E: PairingEngine>(){
+  let rng = &mut test_rng();
+  let size: usize = 8;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let domain: GeneralEvaluationDomain<E::Fr> = EvaluationDomain::<E::Fr>::new(size).unwrap();
+  let omega = group_gen(&domain);
+
+  let mut batch = asvc::BatchVerifier::<E>::new();
+  let openings = [vec![0, 1, 5], vec![2], vec![3, 7]];
+  for points in openings.iter() {
+    let values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+    let c = asvc::commit(&params.proving_key, values.clone()).unwrap();
+    let proof = asvc::prove_pos(&params.proving_key, values.clone(), points.clone()).unwrap();
+    let point_values = points.iter().map(|i| values[*i as usize]).collect();
+    batch.add(&c, point_values, points.clone(), &proof);
+  }
+  assert_eq!(batch.len(), 3);
+
+  let start = Instant::now();
+  let failed = batch.verify(&params.verifying_key, omega, rng).unwrap();
+  println!("ASVC Batch Verify Time: {:?}", start.elapsed());
+  assert!(failed.is_empty());
+
+  // a fourth opening with a wrong value
+  let values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+  let c = asvc::commit(&params.proving_key, values.clone()).unwrap();
+  let proof = asvc::prove_pos(&params.proving_key, values.clone(), vec![4]).unwrap();
+  batch.add(&c, vec![values[5]], vec![4], &proof);
+  assert_eq!(batch.verify(&params.verifying_key, omega, rng).unwrap(), vec![3]);
+
+  assert!(asvc::BatchVerifier::<E>::new().verify(&params.verifying_key, omega, rng).unwrap().is_empty());
+}
+
+fn test_cross_commitment_aggregation<E: PairingEngine>(){
+  let rng = &mut test_rng();
+  let size: usize = 8;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let domain: GeneralEvaluationDomain<E::Fr> = EvaluationDomain::<E::Fr>::new(size).unwrap();
+  let omega = group_gen(&domain);
+
+  let mut openings = Vec::new();
+  let mut proofs = Vec::new();
+  for points in [vec![0, 1], vec![1, 6], vec![3]].iter() {
+    let values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+    let c = asvc::commit(&params.proving_key, values.clone()).unwrap();
+    proofs.push(asvc::prove_pos(&params.proving_key, values.clone(), points.clone()).unwrap());
+    openings.push((c, points.clone(), points.iter().map(|i| values[*i as usize]).collect::<Vec<E::Fr>>()));
+  }
+
+  let start = Instant::now();
+  let proof = asvc::aggregate_across_commitments(&openings, proofs.clone()).unwrap();
+  let rs = asvc::verify_across_commitments(&params.verifying_key, &openings, &proof, omega).unwrap();
+  println!("ASVC Cross-Commitment Aggregate/Verify Time: {:?}", start.elapsed());
+  assert!(rs);
+
+  // a wrong value in one opening
+  let mut wrong = openings.clone();
+  wrong[1].2[0] = wrong[1].2[0].add(&E::Fr::from(1u64));
+  assert!(!asvc::verify_across_commitments(&params.verifying_key, &wrong, &proof, omega).unwrap());
+
+  // the scalars depend on the order of the openings
+  let mut swapped_openings = openings.clone();
+  swapped_openings.swap(0, 2);
+  assert!(!asvc::verify_across_commitments(&params.verifying_key, &swapped_openings, &proof, omega).unwrap());
+  let mut swapped_proofs = proofs;
+  swapped_proofs.swap(0, 2);
+  let proof = asvc::aggregate_across_commitments(&swapped_openings, swapped_proofs).unwrap();
+  assert!(asvc::verify_across_commitments(&params.verifying_key, &swapped_openings, &proof, omega).unwrap());
+}
+
+fn test_context<E: PairingEngine>(){
+  let rng = &mut test_rng();
+  let size: usize = 8;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let ctx = asvc::Asvc::new(params.clone()).unwrap();
+  assert_eq!(ctx.size(), size);
+  let domain: GeneralEvaluationDomain<E::Fr> = EvaluationDomain::<E::Fr>::new(size).unwrap();
+  assert_eq!(ctx.omega(), group_gen(&domain));
+
+  let values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+  let c = ctx.commit(values.clone()).unwrap();
+  let points = vec![0, 1, 5];
+  let point_values: Vec<E::Fr> = points.iter().map(|i| values[*i as usize]).collect();
+
+  let start = Instant::now();
+  let proof = ctx.prove_pos(values.clone(), points.clone()).unwrap();
+  assert!(ctx.verify_pos(&c, point_values.clone(), points.clone(), &proof).unwrap());
+  println!("ASVC Context Prove/Verify Time: {:?}", start.elapsed());
+
+  let proofs = ctx.prove_all(values.clone()).unwrap();
+  let point_proofs: Vec<_> = points.iter().map(|i| proofs[*i as usize].clone()).collect();
+  assert!(ctx.aggregate_proofs(points.clone(), point_proofs).unwrap() == proof);
+
+  // update v_1 by delta and the proof of position 5
+  let delta = E::Fr::from(3u64);
+  let upk_1 = ctx.update_key(1).unwrap().clone();
+  let upk_5 = ctx.update_key(5).unwrap().clone();
+  assert!(ctx.verify_upk(1, &upk_1).unwrap());
+  let old = c.clone();
+  let c = ctx.update_commit(&c, delta, 1).unwrap();
+  let proof_5 = ctx.update_proof(&proofs[5], delta, 5, 1).unwrap();
+  assert!(ctx.verify_pos(&c, vec![values[5]], vec![5], &proof_5).unwrap());
+  let mut updated = values.clone();
+  updated[1] = updated[1].add(&delta);
+  assert!(c == ctx.commit(updated).unwrap());
+
+  // a verifier gets upk_i along with the update and checks it first
+  let verifier = asvc::Asvc::from_verifying_key(params.verifying_key.clone()).unwrap();
+  assert!(matches!(verifier.update_commit(&old, delta, 1), Err(asvc::CustomError::MissingProvingKey)));
+  assert!(verifier.update_commit_with_upk(&old, delta, 1, &upk_1).unwrap() == c);
+  assert!(verifier.update_proof_with_upks(&proofs[5], delta, 5, 1, &upk_5, &upk_1).unwrap() == proof_5);
+  assert!(matches!(verifier.update_commit_with_upk(&old, delta, 1, &upk_5),
+                   Err(asvc::CustomError::InvalidUpdateKey(1))));
+  assert!(matches!(verifier.update_proof_with_upks(&proofs[5], delta, 5, 1, &upk_1, &upk_1),
+                   Err(asvc::CustomError::InvalidUpdateKey(5))));
+
+  // positions outside [0, n) are errors, not table lookups
+  assert!(matches!(
+    ctx.verify_pos(&c, vec![values[0]], vec![size as u32], &proof),
+    Err(asvc::CustomError::IndexOutOfRange { .. })
+  ));
+  assert!(matches!(ctx.verify_upk(size as u32, &upk_1), Err(asvc::CustomError::IndexOutOfRange { .. })));
+  assert!(matches!(ctx.update_key(size as u32), Err(asvc::CustomError::IndexOutOfRange { .. })));
+
+  // a verifier only context
+  let verifier = asvc::Asvc::from_verifying_key(params.verifying_key.clone()).unwrap();
+  assert!(verifier.verify_pos(&c, vec![values[5]], vec![5], &proof_5).unwrap());
+  assert!(matches!(verifier.commit(values), Err(asvc::CustomError::MissingProvingKey)));
+
+  // keys of different sizes don't make a context
+  let mut mixed = params;
+  mixed.proving_key = asvc::key_gen::<E,_>(4, rng).unwrap().proving_key;
+  assert!(matches!(asvc::Asvc::new(mixed), Err(asvc::CustomError::MalformedKey)));
+}
+
+fn test_ceremony<E: PairingEngine>(){
+  use asvc::{Compression, Encode};
+  let rng = &mut test_rng();
+  let size: usize = 8;
+
+  // three participants; the second one works on its own copy of the powers
+  let mut ceremony = asvc::Ceremony::<E>::new(size).unwrap();
+  ceremony.contribute(rng).unwrap();
+  let contribution = ceremony.current().unwrap().contribute(rng).unwrap();
+  ceremony.add(contribution.clone()).unwrap();
+  ceremony.contribute(rng).unwrap();
+  assert!(ceremony.verify().unwrap());
+
+  // the transcript survives encoding
+  let bytes = ceremony.to_bytes(Compression::Compressed).unwrap();
+  let decoded = asvc::Ceremony::<E>::from_bytes(&bytes).unwrap();
+  assert!(decoded == ceremony);
+  assert!(decoded.verify().unwrap());
+
+  // a transcript states its own n, which must match its powers before anything is allocated
+  let set_size = |n: u64| {
+    let mut bytes = bytes.clone();
+    bytes[3..11].copy_from_slice(&n.to_le_bytes());
+    asvc::Ceremony::<E>::from_bytes(&bytes).unwrap()
+  };
+  assert!(matches!(set_size(1 << 16).verify(), Err(asvc::CustomError::MalformedKey)));
+  assert!(matches!(set_size(1 << 16).current(), Err(asvc::CustomError::MalformedKey)));
+  assert!(matches!(set_size(12).verify(), Err(asvc::CustomError::UnsupportedDomainSize(12))));
+  assert!(matches!(set_size(u64::MAX).verify(), Err(asvc::CustomError::UnsupportedDomainSize(_))));
+  assert!(set_size(size as u64).verify().unwrap());
+
+  // replaying a contribution on other powers, or fresh powers without a proof, fails
+  assert!(matches!(ceremony.clone().add(contribution.clone()), Err(asvc::CustomError::InvalidContribution(3))));
+  let mut forged = contribution;
+  forged.powers = asvc::Ceremony::<E>::new(size).unwrap().current().unwrap().contribute(rng).unwrap().powers;
+  let mut tampered = asvc::Ceremony::<E>::new(size).unwrap();
+  tampered.contribute(rng).unwrap();
+  assert!(matches!(tampered.add(forged), Err(asvc::CustomError::InvalidContribution(1))));
+
+  let start = Instant::now();
+  let params = ceremony.into_parameters().unwrap();
+  println!("ASVC Ceremony Parameters Time: {:?}", start.elapsed());
+
+  let ctx = asvc::Asvc::new(params).unwrap();
+  for i in 0..size as u32 {
+    assert!(ctx.verify_upk(i, ctx.update_key(i).unwrap()).unwrap());
+  }
+  let values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+  let c = ctx.commit(values.clone()).unwrap();
+  let proof = ctx.prove_pos(values.clone(), vec![2, 6]).unwrap();
+  assert!(ctx.verify_pos(&c, vec![values[2], values[6]], vec![2, 6], &proof).unwrap());
+
+  // from the powers alone we get the same keys key_gen derives from τ
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let derived = asvc::Parameters::<E>::from_powers(
+    params.verifying_key.list_g1_tau_i.clone(),
+    params.verifying_key.list_g2_tau_i.clone(),
+  ).unwrap();
+  assert!(derived == params);
+
+  // τ = ω: τ^n = 1, so a is the identity and τ is no secret
+  let domain: GeneralEvaluationDomain<E::Fr> = EvaluationDomain::<E::Fr>::new(size).unwrap();
+  let (g1, g2) = powers_of::<E>(group_gen(&domain), size);
+  assert!(matches!(asvc::Parameters::<E>::from_powers(g1, g2),
+                   Err(asvc::CustomError::InvalidParameters(asvc::ParameterCheck::A))));
+
+  assert!(matches!(asvc::Ceremony::<E>::new(0), Err(asvc::CustomError::UnsupportedDomainSize(0))));
+  assert!(matches!(asvc::Ceremony::<E>::new(size).unwrap().into_parameters(), Err(asvc::CustomError::InvalidContribution(0))));
+}
+
+// a snarkjs style .ptau file with the powers of tau up to 2^(power+1) - 2
+fn write_ptau(tau: ark_bls12_381::Fr, power: u32) -> Vec<u8> {
+  use ark_bls12_381::{Fq, Fr, G1Affine, G2Affine};
+  use ark_ec::{AffineCurve, ProjectiveCurve};
+  use ark_ff::{FpParameters, PrimeField};
+
+  fn fq(bytes: &mut Vec<u8>, x: &Fq) {
+    for limb in (x.0).0.iter() {
+      bytes.extend_from_slice(&limb.to_le_bytes());
+    }
+  }
+  fn section(bytes: &mut Vec<u8>, kind: u32, body: Vec<u8>) {
+    bytes.extend_from_slice(&kind.to_le_bytes());
+    bytes.extend_from_slice(&(body.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&body);
+  }
+
+  let mut bytes = b"ptau".to_vec();
+  bytes.extend_from_slice(&1u32.to_le_bytes());
+  bytes.extend_from_slice(&3u32.to_le_bytes());
+
+  let mut header = 48u32.to_le_bytes().to_vec();
+  for limb in <Fq as PrimeField>::Params::MODULUS.0.iter() {
+    header.extend_from_slice(&limb.to_le_bytes());
+  }
+  header.extend_from_slice(&power.to_le_bytes());
+  header.extend_from_slice(&power.to_le_bytes());
+  section(&mut bytes, 1, header);
+
+  let mut tau_g1 = Vec::new();
+  let mut tau_g2 = Vec::new();
+  let mut tau_i = Fr::from(1u64);
+  for i in 0..(1usize << (power + 1)) - 1 {
+    let p = G1Affine::prime_subgroup_generator().mul(tau_i).into_affine();
+    fq(&mut tau_g1, &p.x);
+    fq(&mut tau_g1, &p.y);
+    if i < 1 << power {
+      let q = G2Affine::prime_subgroup_generator().mul(tau_i).into_affine();
+      for c in [q.x.c0, q.x.c1, q.y.c0, q.y.c1].iter() {
+        fq(&mut tau_g2, c);
+      }
+    }
+    tau_i *= tau;
+  }
+  // sections need not come in order
+  section(&mut bytes, 3, tau_g2);
+  section(&mut bytes, 2, tau_g1);
+  bytes
+}
+
+#[test]
+fn test_ptau(){
+  use ark_bls12_381::Fr;
+  use std::io::Cursor;
+  let rng = &mut test_rng();
+  let size: usize = 8;
+  let bytes = write_ptau(Fr::rand(rng), 4);
+
+  let path = std::env::temp_dir().join(format!("asvc-test-{}.ptau", std::process::id()));
+  std::fs::write(&path, &bytes).unwrap();
+  let start = Instant::now();
+  let params = asvc::load_ptau(&path, size).unwrap();
+  println!("ASVC Load Ptau Time: {:?}", start.elapsed());
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(params.proving_key.list_g1_tau_i.len(), size + 1);
+  assert_eq!(params.verifying_key.list_g2_tau_i.len(), size + 1);
+
+  let ctx = asvc::Asvc::new(params).unwrap();
+  let values: Vec<Fr> = (0..size).map(|_| Fr::rand(rng)).collect();
+  let c = ctx.commit(values.clone()).unwrap();
+  let proof = ctx.prove_pos(values.clone(), vec![1, 4, 7]).unwrap();
+  assert!(ctx.verify_pos(&c, vec![values[1], values[4], values[7]], vec![1, 4, 7], &proof).unwrap());
+  assert!(ctx.verify_upk(3, ctx.update_key(3).unwrap()).unwrap());
+
+  // the file holds powers up to 30
+  assert!(asvc::read_ptau(&mut Cursor::new(&bytes), 16).is_ok());
+  assert!(matches!(asvc::read_ptau(&mut Cursor::new(&bytes), 32), Err(asvc::CustomError::VectorTooLong { size: 32, max: 30 })));
+
+  let mut wrong = bytes.clone();
+  wrong[0] = b'x';
+  assert!(matches!(asvc::read_ptau(&mut Cursor::new(&wrong), size), Err(asvc::CustomError::InvalidHeader)));
+
+  // swap g^τ and g^(τ^2): every point is valid, the powers are not
+  let start = bytes.len() - 31 * 96;
+  let mut swapped = bytes.clone();
+  swapped[start + 96..start + 192].copy_from_slice(&bytes[start + 192..start + 288]);
+  swapped[start + 192..start + 288].copy_from_slice(&bytes[start + 96..start + 192]);
+  assert!(matches!(asvc::read_ptau(&mut Cursor::new(&swapped), size), Err(asvc::CustomError::InvalidPowers)));
+
+  // a point off the curve
+  let mut off_curve = bytes.clone();
+  off_curve[start + 96] ^= 1;
+  assert!(matches!(asvc::read_ptau(&mut Cursor::new(&off_curve), size), Err(asvc::CustomError::SerializationError(_))));
+
+  // sizes and counts past the end of the file: "ptau" | 1 | count | type | size
+  let crafted = |count: u32, size: u64| {
+    let mut bytes = b"ptau".to_vec();
+    bytes.extend_from_slice(&1u32.to_le_bytes());
+    bytes.extend_from_slice(&count.to_le_bytes());
+    bytes.extend_from_slice(&1u32.to_le_bytes());
+    bytes.extend_from_slice(&size.to_le_bytes());
+    bytes
+  };
+  assert_eq!(crafted(1, u64::MAX).len(), 24);
+  for (count, section_size) in [(1, u64::MAX), (1, 1), (2, 0), (u32::MAX, 0)].iter() {
+    assert!(matches!(asvc::read_ptau(&mut Cursor::new(crafted(*count, *section_size)), size),
+                     Err(asvc::CustomError::SerializationError(ark_serialize::SerializationError::InvalidData))));
+  }
+
+  // power 31, the largest: 2^32 - 1 powers in G1, counted without overflow on 32-bit targets
+  let mut header = bytes[..12].to_vec();
+  header[8..12].copy_from_slice(&1u32.to_le_bytes());
+  let mut body = bytes[24..24 + 52].to_vec();
+  body.extend_from_slice(&31u32.to_le_bytes());
+  body.extend_from_slice(&31u32.to_le_bytes());
+  header.extend_from_slice(&1u32.to_le_bytes());
+  header.extend_from_slice(&(body.len() as u64).to_le_bytes());
+  header.extend_from_slice(&body);
+  assert!(matches!(asvc::read_ptau(&mut Cursor::new(&header), 8), Err(asvc::CustomError::InvalidHeader)));
+  assert!(matches!(asvc::read_ptau(&mut Cursor::new(&header), 1 << 32),
+                   Err(asvc::CustomError::VectorTooLong { size: 4294967296, max: 4294967294 })));
+}
+
+fn test_lagrange_keys<E: PairingEngine>(){
+  let rng = &mut test_rng();
+  for size in [1usize, 2, 8, 64].iter() {
+    let params = asvc::key_gen::<E,_>(*size, rng).unwrap();
+    let start = Instant::now();
+    let list_l_i = asvc::lagrange_keys::<E>(&params.proving_key.list_g1_tau_i).unwrap();
+    println!("ASVC Lagrange Keys (n = {}) Time: {:?}", size, start.elapsed());
+    assert!(list_l_i == params.proving_key.list_l_i);
+  }
+
+  let params = asvc::key_gen::<E,_>(8, rng).unwrap();
+  assert!(matches!(asvc::lagrange_keys::<E>(&params.proving_key.list_g1_tau_i[..1]), Err(asvc::CustomError::MalformedKey)));
+  assert!(matches!(asvc::lagrange_keys::<E>(&params.proving_key.list_g1_tau_i[..7]), Err(asvc::CustomError::UnsupportedDomainSize(6))));
+}
+
+fn test_update_keys<E: PairingEngine>(){
+  let rng = &mut test_rng();
+  for size in [1usize, 2, 8, 64].iter() {
+    let params = asvc::key_gen::<E,_>(*size, rng).unwrap();
+    let start = Instant::now();
+    let upks = asvc::update_keys::<E>(&params.proving_key.list_g1_tau_i).unwrap();
+    println!("ASVC Update Keys (n = {}) Time: {:?}", size, start.elapsed());
+    assert!(upks == params.proving_key.list_update_keys);
+  }
+
+  let params = asvc::key_gen::<E,_>(8, rng).unwrap();
+  assert!(matches!(asvc::update_keys::<E>(&[]), Err(asvc::CustomError::MalformedKey)));
+  assert!(matches!(asvc::update_keys::<E>(&params.proving_key.list_g1_tau_i[..4]), Err(asvc::CustomError::UnsupportedDomainSize(3))));
+}
+
+fn test_key_gen_progress<E: PairingEngine>(){
+  let size: usize = 64;
+  let mut calls = Vec::new();
+  let start = Instant::now();
+  let params = asvc::key_gen_with_progress::<E,_,_>(size, &mut test_rng(), |done, total| calls.push((done, total))).unwrap();
+  println!("ASVC KeyGen (n = {}) Time: {:?}", size, start.elapsed());
+  assert_eq!(calls.last(), Some(&(size, size)));
+  assert!(calls.windows(2).all(|w| w[0].0 < w[1].0));
+
+  // same rng, same parameters as key_gen
+  assert!(params == asvc::key_gen::<E,_>(size, &mut test_rng()).unwrap());
+  let ctx = asvc::Asvc::new(params).unwrap();
+  for i in 0..size as u32 {
+    assert!(ctx.verify_upk(i, ctx.update_key(i).unwrap()).unwrap());
+  }
+}
+
+fn test_trim<E: PairingEngine>(){
+  use asvc::CustomError;
+  let rng = &mut test_rng();
+  let params = asvc::key_gen::<E,_>(16, rng).unwrap();
+
+  // a shorter vector with openings of up to 3 positions
+  let size: usize = 8;
+  let start = Instant::now();
+  let trimmed = params.trim(size, 3).unwrap();
+  println!("ASVC Trim Time: {:?}", start.elapsed());
+  assert_eq!(trimmed.verifying_key.n, size as u64);
+  assert_eq!(trimmed.verifying_key.list_g1_tau_i.len(), 3);
+  assert_eq!(trimmed.verifying_key.list_g2_tau_i.len(), 4);
+  assert_eq!(trimmed.proving_key.list_l_i.len(), size);
+  // the same keys as parameters built for size 8 from the same τ
+  let smaller = asvc::Parameters::<E>::from_powers(
+    params.proving_key.list_g1_tau_i[..size + 1].to_vec(),
+    params.verifying_key.list_g2_tau_i[..size + 1].to_vec(),
+  ).unwrap();
+  assert!(trimmed.proving_key == smaller.proving_key);
+
+  let ctx = asvc::Asvc::new(trimmed.clone()).unwrap();
+  let values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+  let c = ctx.commit(values.clone()).unwrap();
+  let proof = ctx.prove_pos(values.clone(), vec![0, 3, 7]).unwrap();
+  assert!(ctx.verify_pos(&c, vec![values[0], values[3], values[7]], vec![0, 3, 7], &proof).unwrap());
+  assert!(ctx.verify_upk(5, ctx.update_key(5).unwrap()).unwrap());
+
+  // the compact verifying key on its own
+  let verifier = asvc::Asvc::from_verifying_key(trimmed.verifying_key.clone()).unwrap();
+  assert!(verifier.verify_pos(&c, vec![values[0], values[3], values[7]], vec![0, 3, 7], &proof).unwrap());
+
+  // beyond the bounds
+  let proof = ctx.prove_pos(values.clone(), vec![0, 1, 2, 3]).unwrap();
+  assert!(matches!(
+    verifier.verify_pos(&c, values[..4].to_vec(), vec![0, 1, 2, 3], &proof),
+    Err(CustomError::SubvectorTooLarge { size: 4, max: 3 })
+  ));
+  assert!(matches!(verifier.verify_pos(&c, vec![values[0]], vec![8], &proof), Err(CustomError::IndexOutOfRange { index: 8, size: 8 })));
+  assert!(matches!(ctx.commit(vec![E::Fr::from(1u64); 9]), Err(CustomError::VectorTooLong { size: 9, max: 8 })));
+
+  // trimming the same size keeps the proving key
+  assert!(params.trim(16, 16).unwrap().proving_key == params.proving_key);
+  assert!(matches!(params.trim(32, 4), Err(CustomError::VectorTooLong { size: 32, max: 16 })));
+  assert!(matches!(params.trim(8, 9), Err(CustomError::SubvectorTooLarge { size: 9, max: 8 })));
+  assert!(matches!(params.trim(8, 0), Err(CustomError::EmptyIndexSet)));
+  assert!(matches!(params.trim(6, 2), Err(CustomError::UnsupportedDomainSize(6))));
+  assert!(matches!(trimmed.trim(8, 4), Err(CustomError::SubvectorTooLarge { size: 4, max: 3 })));
+
+  // n comes from the key file: 2^32, the domain cap, costs a verifier nothing up front;
+  // BN254 has no domain that large
+  let mut huge = trimmed.verifying_key.clone();
+  huge.n = 1 << 32;
+  if <<E::Fr as FftField>::FftParams as ark_ff::FftParameters>::TWO_ADICITY >= 32 {
+    let verifier = asvc::Asvc::from_verifying_key(huge.clone()).unwrap();
+    assert_eq!(verifier.size() as u64, 1 << 32);
+    assert!(!verifier.verify_pos(&c, vec![values[3]], vec![3], &ctx.prove_pos(values.clone(), vec![3]).unwrap()).unwrap());
+  } else {
+    assert!(matches!(asvc::Asvc::from_verifying_key(huge.clone()), Err(CustomError::UnsupportedDomainSize(_))));
+  }
+  // past the cap, or fewer positions than the key has powers
+  for n in [1u64 << 33, u64::MAX].iter() {
+    huge.n = *n;
+    assert!(matches!(asvc::Asvc::from_verifying_key(huge.clone()), Err(CustomError::UnsupportedDomainSize(_))));
+  }
+  huge.n = 2;
+  assert!(matches!(asvc::Asvc::from_verifying_key(huge), Err(CustomError::MalformedKey)));
+}
+
+fn test_update_subvector_proof<E: PairingEngine>(){
+  let rng = &mut test_rng();
+  let size: usize = 8;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let ctx = asvc::Asvc::new(params.clone()).unwrap();
+  let upks = &params.proving_key.list_update_keys;
+
+  let mut values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+  let mut c = ctx.commit(values.clone()).unwrap();
+  let points = vec![1, 4, 6];
+  let point_upks: Vec<_> = points.iter().map(|i| upks[*i as usize].clone()).collect();
+  let mut proof = ctx.prove_pos(values.clone(), points.clone()).unwrap();
+
+  // a change outside I, then one inside I
+  for j in [3u32, 4].iter() {
+    let delta = E::Fr::rand(rng);
+    let start = Instant::now();
+    proof = ctx.update_subvector_proof(&proof, delta, points.clone(), *j).unwrap();
+    println!("ASVC Update Subvector Proof Time: {:?}", start.elapsed());
+    c = ctx.update_commit(&c, delta, *j).unwrap();
+    values[*j as usize] = values[*j as usize].add(&delta);
+
+    let point_values: Vec<E::Fr> = points.iter().map(|i| values[*i as usize]).collect();
+    assert!(ctx.verify_pos(&c, point_values, points.clone(), &proof).unwrap());
+    assert!(proof == ctx.prove_pos(values.clone(), points.clone()).unwrap());
+  }
+
+  // the free function with ω and n
+  let domain: GeneralEvaluationDomain<E::Fr> = EvaluationDomain::<E::Fr>::new(size).unwrap();
+  let delta = E::Fr::from(5u64);
+  let updated = asvc::update_subvector_proof(&proof, delta, points.clone(), 6, &point_upks, &upks[6], group_gen(&domain), size).unwrap();
+  values[6] = values[6].add(&delta);
+  assert!(updated == ctx.prove_pos(values, points.clone()).unwrap());
+
+  let verifier = asvc::Asvc::from_verifying_key(params.verifying_key.clone()).unwrap();
+  assert!(verifier.update_subvector_proof_with_upks(&proof, delta, points.clone(), 6, &point_upks, &upks[6]).unwrap() == updated);
+  assert!(matches!(
+    verifier.update_subvector_proof_with_upks(&proof, delta, points.clone(), 6, &point_upks, &upks[2]),
+    Err(asvc::CustomError::InvalidUpdateKey(6))
+  ));
+  assert!(matches!(
+    verifier.update_subvector_proof_with_upks(&proof, delta, points.clone(), 2, &point_upks[..2], &upks[2]),
+    Err(asvc::CustomError::LengthMismatch { expected: 3, found: 2 })
+  ));
+  assert!(matches!(
+    ctx.update_subvector_proof(&proof, delta, points, 8),
+    Err(asvc::CustomError::IndexOutOfRange { index: 8, size: 8 })
+  ));
+}
+
+fn test_update_commit_batch<E: PairingEngine>(){
+  let rng = &mut test_rng();
+  let size: usize = 16;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let ctx = asvc::Asvc::new(params.clone()).unwrap();
+  let upks = &params.proving_key.list_update_keys;
+
+  let mut values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+  let c = ctx.commit(values.clone()).unwrap();
+
+  // position 9 changes twice
+  let updates: Vec<(u32, E::Fr)> = [2u32, 9, 15, 9, 0].iter().map(|i| (*i, E::Fr::rand(rng))).collect();
+  let update_keys: Vec<_> = updates.iter().map(|(i, _)| upks[*i as usize].clone()).collect();
+  let start = Instant::now();
+  let batched = ctx.update_commit_batch(&c, &updates).unwrap();
+  println!("ASVC Update Commit Batch Time: {:?}", start.elapsed());
+
+  let mut one_by_one = c.clone();
+  for (i, delta) in updates.iter() {
+    one_by_one = ctx.update_commit(&one_by_one, *delta, *i).unwrap();
+    values[*i as usize] = values[*i as usize].add(delta);
+  }
+  assert!(batched == one_by_one);
+  assert!(batched == ctx.commit(values).unwrap());
+
+  let domain: GeneralEvaluationDomain<E::Fr> = EvaluationDomain::<E::Fr>::new(size).unwrap();
+  assert!(asvc::update_commit_batch(&c, &updates, &update_keys, group_gen(&domain), size).unwrap() == batched);
+  assert!(ctx.update_commit_batch(&c, &[]).unwrap() == c);
+  assert!(matches!(
+    ctx.update_commit_batch(&c, &[(16, E::Fr::from(1u64))]),
+    Err(asvc::CustomError::IndexOutOfRange { index: 16, size: 16 })
+  ));
+
+  let verifier = asvc::Asvc::from_verifying_key(params.verifying_key.clone()).unwrap();
+  assert!(verifier.update_commit_batch_with_upks(&c, &updates, &update_keys).unwrap() == batched);
+  assert!(matches!(
+    verifier.update_commit_batch_with_upks(&c, &updates, &update_keys[1..]),
+    Err(asvc::CustomError::LengthMismatch { expected: 5, found: 4 })
+  ));
+  let mut swapped = update_keys.clone();
+  swapped.swap(0, 1);
+  assert!(matches!(
+    verifier.update_commit_batch_with_upks(&c, &updates, &swapped),
+    Err(asvc::CustomError::InvalidUpdateKey(2))
+  ));
+}
+
+fn test_verify_all_upks<E: PairingEngine>(){
+  let rng = &mut test_rng();
+  let size: usize = 64;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let ctx = asvc::Asvc::from_verifying_key(params.verifying_key.clone()).unwrap();
+  let prk = &params.proving_key;
+
+  let start = Instant::now();
+  assert!(ctx.verify_all_upks(prk, rng).unwrap());
+  println!("ASVC Verify All UPKs (n = {}) Time: {:?}", size, start.elapsed());
+  let domain: GeneralEvaluationDomain<E::Fr> = EvaluationDomain::<E::Fr>::new(size).unwrap();
+  assert!(asvc::verify_all_upks(&params.verifying_key, prk, group_gen(&domain), rng).unwrap());
+
+  // one bad a_i, u_i or l_i, or two swapped keys
+  let mut bad = prk.clone();
+  bad.list_update_keys[7].a_i = bad.list_update_keys[8].a_i;
+  assert!(!ctx.verify_all_upks(&bad, rng).unwrap());
+  let mut bad = prk.clone();
+  bad.list_update_keys[63].u_i = bad.list_update_keys[0].u_i;
+  assert!(!ctx.verify_all_upks(&bad, rng).unwrap());
+  let mut bad = prk.clone();
+  bad.list_l_i[5] = bad.list_l_i[6];
+  assert!(!ctx.verify_all_upks(&bad, rng).unwrap());
+  let mut bad = prk.clone();
+  bad.list_update_keys.swap(1, 2);
+  bad.list_l_i.swap(1, 2);
+  assert!(!ctx.verify_all_upks(&bad, rng).unwrap());
+
+  let mut short = prk.clone();
+  short.list_l_i.pop();
+  assert!(matches!(ctx.verify_all_upks(&short, rng), Err(asvc::CustomError::LengthMismatch { expected: 64, found: 63 })));
+}
+
+fn test_hiding<E: PairingEngine>(){
+  let rng = &mut test_rng();
+  let size: usize = 16;
+  let params = asvc::key_gen_hiding::<E,_>(size, rng).unwrap();
+  let ctx = asvc::Asvc::new(params.clone()).unwrap();
+  let domain: GeneralEvaluationDomain<E::Fr> = EvaluationDomain::<E::Fr>::new(size).unwrap();
+
+  // the same vector commits to a different point every time
+  let values: Vec<E::Fr> = (0..size).map(|_| E::Fr::from(5u64)).collect();
+  let (c, blinding) = ctx.commit_hiding(values.clone(), rng).unwrap();
+  let (other, _) = ctx.commit_hiding(values.clone(), rng).unwrap();
+  assert!(c != other && c != ctx.commit(values.clone()).unwrap());
+
+  let start = Instant::now();
+  let points = vec![2, 3, 11];
+  let point_values: Vec<E::Fr> = points.iter().map(|i| values[*i as usize]).collect();
+  let point_blindings: Vec<E::Fr> = points.iter().map(|i| blinding[*i as usize]).collect();
+  let proof = ctx.prove_pos_hiding(values.clone(), blinding.clone(), points.clone()).unwrap();
+  assert!(ctx.verify_pos_hiding(&c, point_values.clone(), point_blindings.clone(), points.clone(), &proof).unwrap());
+  println!("ASVC Hiding Prove/Verify Time: {:?}", start.elapsed());
+  assert!(asvc::verify_pos_hiding(
+    &params.verifying_key, &c, point_values.clone(), point_blindings.clone(), points.clone(), &proof,
+    group_gen(&domain),
+  ).unwrap());
+
+  // a wrong value or blinding fails
+  let mut wrong = point_blindings.clone();
+  wrong[1] = wrong[1].add(&E::Fr::from(1u64));
+  assert!(!ctx.verify_pos_hiding(&c, point_values.clone(), wrong, points.clone(), &proof).unwrap());
+  let mut wrong = point_values.clone();
+  wrong[0] = E::Fr::from(6u64);
+  assert!(!ctx.verify_pos_hiding(&c, wrong, point_blindings.clone(), points.clone(), &proof).unwrap());
+
+  // updates leave the blinding alone
+  let delta = E::Fr::from(3u64);
+  let c = ctx.update_commit(&c, delta, 3).unwrap();
+  let single = ctx.prove_pos_hiding(values.clone(), blinding.clone(), vec![11]).unwrap();
+  let single = ctx.update_proof(&single, delta, 11, 3).unwrap();
+  assert!(ctx.verify_pos_hiding(&c, vec![values[11]], vec![blinding[11]], vec![11], &single).unwrap());
+
+  // trimming keeps the hiding key
+  let trimmed = params.trim(8, 2).unwrap();
+  let small = asvc::Asvc::new(trimmed).unwrap();
+  let (c, blinding) = small.commit_hiding(values[..8].to_vec(), rng).unwrap();
+  let proof = small.prove_pos_hiding(values[..8].to_vec(), blinding.clone(), vec![1, 6]).unwrap();
+  assert!(small.verify_pos_hiding(&c, vec![values[1], values[6]], vec![blinding[1], blinding[6]], vec![1, 6], &proof).unwrap());
+
+  // keys without h
+  let plain = asvc::Asvc::new(asvc::key_gen::<E,_>(size, rng).unwrap()).unwrap();
+  assert!(matches!(plain.commit_hiding(values.clone(), rng), Err(asvc::CustomError::MissingHidingKey)));
+  assert!(matches!(
+    plain.verify_pos_hiding(&c, point_values, point_blindings, points, &proof),
+    Err(asvc::CustomError::MissingHidingKey)
+  ));
+}
+
+fn test_linear<E: PairingEngine>(){
+  let rng = &mut test_rng();
+  let size: usize = 16;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let ctx = asvc::Asvc::new(params.clone()).unwrap();
+
+  let values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+  let c = ctx.commit(values.clone()).unwrap();
+
+  // sum of all values
+  let start = Instant::now();
+  let ones = vec![E::Fr::from(1u64); size];
+  let sum = values.iter().fold(E::Fr::from(0u64), |acc, v| acc.add(v));
+  let proof = ctx.prove_linear(values.clone(), ones.clone()).unwrap();
+  assert!(ctx.verify_linear(&c, ones.clone(), sum, &proof).unwrap());
+  println!("ASVC Linear Prove/Verify Time: {:?}", start.elapsed());
+  assert!(!ctx.verify_linear(&c, ones.clone(), sum.add(&E::Fr::from(1u64)), &proof).unwrap());
+  assert!(asvc::verify_linear(&params.verifying_key, &c, ones, sum, &proof).unwrap());
+
+  // weighted sum of a prefix, the rest weighted 0
+  let weights: Vec<E::Fr> = (0..5).map(|_| E::Fr::rand(rng)).collect();
+  let result = weights.iter().zip(values.iter()).fold(E::Fr::from(0u64), |acc, (w, v)| acc.add(&(*w * v)));
+  let proof = asvc::prove_linear(&params.proving_key, values.clone(), weights.clone()).unwrap();
+  assert!(ctx.verify_linear(&c, weights.clone(), result, &proof).unwrap());
+  let mut other = weights.clone();
+  other[2] = other[2].add(&E::Fr::from(1u64));
+  assert!(!ctx.verify_linear(&c, other, result, &proof).unwrap());
+
+  // a proof whose r is not of degree ≤ n-2 fails the degree check
+  let mut forged = proof.clone();
+  forged.r_shifted = forged.r;
+  assert!(!ctx.verify_linear(&c, weights.clone(), result, &forged).unwrap());
+
+  // a trimmed verifying key lacks the G2 powers of W
+  let trimmed = params.trim(size, 2).unwrap();
+  assert!(matches!(
+    asvc::verify_linear(&trimmed.verifying_key, &c, weights, result, &proof),
+    Err(asvc::CustomError::MalformedKey)
+  ));
+  assert!(matches!(ctx.prove_linear(values, vec![]), Err(asvc::CustomError::EmptyVector)));
+}
+
+fn test_byte_blobs<E: PairingEngine>(){
+  let rng = &mut test_rng();
+  let size: usize = 16;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let ctx = asvc::Asvc::new(params.clone()).unwrap();
+  let domain: GeneralEvaluationDomain<E::Fr> = EvaluationDomain::<E::Fr>::new(size).unwrap();
+
+  // 100 bytes in 4 elements of 31 bytes
+  assert_eq!(asvc::bytes_per_element::<E::Fr>(), 31);
+  let data: Vec<u8> = (0..100u32).map(|i| (i * 7 + 255) as u8).collect();
+  let packed = asvc::pack::<E::Fr>(&data);
+  assert_eq!(packed.len(), 4);
+  assert_eq!(&asvc::unpack(&packed).unwrap()[..100], &data[..]);
+  // the length at position 0, then the chunks
+  let c = ctx.commit_bytes(&data).unwrap();
+  let mut blob = vec![E::Fr::from(100u64)];
+  blob.extend(packed);
+  assert!(c == ctx.commit(blob).unwrap());
+  // trailing zero bytes change the commitment
+  assert!(ctx.commit_bytes(b"abc").unwrap() != ctx.commit_bytes(b"abc\0").unwrap());
+
+  // a range across chunks 0..2, one inside chunk 3, and the whole blob
+  let start = Instant::now();
+  let proof = ctx.prove_byte_range(&data, 20..70).unwrap();
+  assert_eq!(proof.chunks.len(), 3);
+  assert!(ctx.verify_byte_range(&c, 20, &data[20..70], &proof).unwrap());
+  println!("ASVC Byte Range Prove/Verify Time: {:?}", start.elapsed());
+  let proof = asvc::prove_byte_range(&params.proving_key, &data, 95..99).unwrap();
+  assert!(asvc::verify_byte_range(&params.verifying_key, &c, 95, &data[95..99], &proof, group_gen(&domain)).unwrap());
+  let whole = ctx.prove_byte_range(&data, 0..100).unwrap();
+  assert!(ctx.verify_byte_range(&c, 0, &data, &whole).unwrap());
+
+  // other bytes, another offset, or bytes the chunks do not hold
+  let mut wrong = data[95..99].to_vec();
+  wrong[3] ^= 1;
+  assert!(!ctx.verify_byte_range(&c, 95, &wrong, &proof).unwrap());
+  assert!(!ctx.verify_byte_range(&c, 94, &data[95..99], &proof).unwrap());
+  assert!(!ctx.verify_byte_range(&c, 20, &data[20..70], &proof).unwrap());
+
+  // the zero bytes after the end of the blob are in the last chunk, but not in the blob
+  assert!(!ctx.verify_byte_range(&c, 99, &[data[99], 0], &proof).unwrap());
+  let mut longer = proof.clone();
+  longer.len = 101;
+  assert!(!ctx.verify_byte_range(&c, 99, &[data[99], 0], &longer).unwrap());
+  assert!(!ctx.verify_byte_range(&c, 95, &data[95..99], &longer).unwrap());
+
+  assert!(matches!(
+    ctx.prove_byte_range(&data, 90..101),
+    Err(asvc::CustomError::InvalidByteRange { start: 90, end: 101, len: 100 })
+  ));
+  assert!(matches!(ctx.verify_byte_range(&c, 3, &[], &proof), Err(asvc::CustomError::InvalidByteRange { .. })));
+}
+
+// the binary only exists with the cli feature
+#[cfg(feature = "cli")]
+#[test]
+fn test_cli(){
+  use std::process::Command;
+  let dir = std::env::temp_dir().join(format!("asvc-cli-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+  let asvc = |args: &[&str]| Command::new(env!("CARGO_BIN_EXE_asvc")).args(args).status().unwrap().code();
+
+  assert_eq!(asvc(&["keygen", "8", &path("params"), &path("vk")]), Some(0));
+  std::fs::write(path("vector"), "1\n2\n3\n4\n5\n6\n7\n8\n").unwrap();
+  std::fs::write(path("values"), "2\n5\n").unwrap();
+  assert_eq!(asvc(&["commit", &path("params"), &path("vector"), &path("c")]), Some(0));
+  assert_eq!(asvc(&["prove", &path("params"), &path("vector"), "1,4", &path("proof")]), Some(0));
+  assert_eq!(asvc(&["verify", &path("vk"), &path("c"), "1,4", &path("values"), &path("proof")]), Some(0));
+  assert_eq!(asvc(&["verify", &path("params"), &path("c"), "1,3", &path("values"), &path("proof")]), Some(1));
+
+  // aggregate single proofs of 1 and 4
+  assert_eq!(asvc(&["prove", &path("params"), &path("vector"), "1", &path("proof_1")]), Some(0));
+  assert_eq!(asvc(&["prove", &path("params"), &path("vector"), "4", &path("proof_4")]), Some(0));
+  assert_eq!(asvc(&["aggregate", &path("params"), "1,4", &path("agg"), &path("proof_1"), &path("proof_4")]), Some(0));
+  assert_eq!(std::fs::read(path("agg")).unwrap(), std::fs::read(path("proof")).unwrap());
+
+  // v_4 -= 3, then the proof of 1 follows
+  assert_eq!(asvc(&["update", &path("params"), "4", "-3", &path("c"), &path("c2")]), Some(0));
+  assert_eq!(asvc(&["update", &path("params"), "4", "-3", &path("proof_1"), &path("proof_1b"), "--proof", "1"]), Some(0));
+  std::fs::write(path("value_1"), "2\n").unwrap();
+  assert_eq!(asvc(&["verify", &path("vk"), &path("c2"), "1", &path("value_1"), &path("proof_1b")]), Some(0));
+  std::fs::write(path("value_4"), "2\n").unwrap();
+  assert_eq!(asvc(&["verify", &path("vk"), &path("c2"), "4", &path("value_4"), &path("proof_4")]), Some(1));
+
+  // an edited n in the key file is an invalid opening, not an abort
+  {
+    use asvc::{Compression, Encode};
+    let mut vk = asvc::VerifyingKey::<ark_bls12_381::Bls12_381>::from_bytes(&std::fs::read(path("vk")).unwrap()).unwrap();
+    vk.n = 1 << 32;
+    std::fs::write(path("vk_huge"), vk.to_bytes(Compression::Compressed).unwrap()).unwrap();
+  }
+  assert_eq!(asvc(&["verify", &path("vk_huge"), &path("c"), "1,4", &path("values"), &path("proof")]), Some(1));
+
+  // errors
+  assert_eq!(asvc(&["verify", &path("vk"), &path("proof"), "1,4", &path("values"), &path("proof")]), Some(2));
+  assert_eq!(asvc(&["frobnicate"]), Some(2));
+  std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_curves(){
+  // the CLI with --curve, and files of one curve rejected by another
+  use std::process::Command;
+  let dir = std::env::temp_dir().join(format!("asvc-curves-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+  let asvc = |args: &[&str]| Command::new(env!("CARGO_BIN_EXE_asvc")).args(args).status().unwrap().code();
+  std::fs::write(path("vector"), "4\n3\n2\n1\n").unwrap();
+  std::fs::write(path("values"), "3\n1\n").unwrap();
+  let mut curves = vec!["bls12-381"];
+  if cfg!(feature = "bn254") {
+    curves.push("bn254");
+  }
+  if cfg!(feature = "bls12_377") {
+    curves.push("bls12-377");
+  }
+  for curve in curves.iter() {
+    let file = |name: &str| path(&format!("{}-{}", curve, name));
+    assert_eq!(asvc(&["--curve", curve, "keygen", "4", &file("params")]), Some(0));
+    assert_eq!(asvc(&["--curve", curve, "commit", &file("params"), &path("vector"), &file("c")]), Some(0));
+    assert_eq!(asvc(&["--curve", curve, "prove", &file("params"), &path("vector"), "1,3", &file("proof")]), Some(0));
+    assert_eq!(asvc(&["--curve", curve, "verify", &file("params"), &file("c"), "1,3", &path("values"), &file("proof")]), Some(0));
+  }
+  if cfg!(feature = "bn254") {
+    assert_eq!(asvc(&["verify", &path("bn254-params"), &path("bn254-c"), "1,3", &path("values"), &path("bn254-proof")]), Some(2));
+  } else {
+    assert_eq!(asvc(&["--curve", "bn254", "keygen", "4", &path("params")]), Some(2));
+  }
+  std::fs::remove_dir_all(&dir).unwrap();
+}
+
+fn test_transcript<E: PairingEngine>(){
+  use asvc::Transcript;
+  let rng = &mut test_rng();
+  let size: usize = 8;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let ctx = asvc::Asvc::new(params.clone()).unwrap();
+
+  // same label and messages give the same challenges; anything else changes them
+  let values: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+  let c = ctx.commit(values.clone()).unwrap();
+  let absorb = |label: &'static [u8], value: &E::Fr| {
+    let mut transcript = Transcript::new(label);
+    transcript.append(b"commitment", &c).unwrap();
+    transcript.append(b"value", value).unwrap();
+    transcript
+  };
+  let challenge: E::Fr = absorb(b"test", &values[0]).challenge_scalar(b"x");
+  assert!(challenge == absorb(b"test", &values[0]).challenge_scalar(b"x"));
+  assert!(challenge != absorb(b"other", &values[0]).challenge_scalar(b"x"));
+  assert!(challenge != absorb(b"test", &values[1]).challenge_scalar(b"x"));
+  assert!(challenge != absorb(b"test", &values[0]).challenge_scalar(b"y"));
+  let mut transcript = absorb(b"test", &values[0]);
+  let first: E::Fr = transcript.challenge_scalar(b"x");
+  assert!(first != transcript.challenge_scalar::<E::Fr>(b"x"));
+
+  // batch verification with challenges from the transcript
+  let mut batch = asvc::BatchVerifier::<E>::new();
+  for points in [vec![0, 3], vec![6]].iter() {
+    let proof = ctx.prove_pos(values.clone(), points.clone()).unwrap();
+    batch.add(&c, points.iter().map(|i| values[*i as usize]).collect(), points.clone(), &proof);
+  }
+  assert!(ctx.verify_batch_with_transcript(&batch, &mut Transcript::new(b"batch")).unwrap().is_empty());
+  let proof = ctx.prove_pos(values.clone(), vec![1]).unwrap();
+  batch.add(&c, vec![values[2]], vec![1], &proof);
+  assert_eq!(batch.verify_with_transcript(&params.verifying_key, ctx.omega(), &mut Transcript::new(b"batch")).unwrap(), vec![2]);
+
+  // cross-commitment aggregation bound to a transcript the verifier has to replay
+  let others: Vec<E::Fr> = (0..size).map(|_| E::Fr::rand(rng)).collect();
+  let d = ctx.commit(others.clone()).unwrap();
+  let openings = vec![(c, vec![0, 2], vec![values[0], values[2]]), (d, vec![5], vec![others[5]])];
+  let proofs = vec![ctx.prove_pos(values, vec![0, 2]).unwrap(), ctx.prove_pos(others, vec![5]).unwrap()];
+  let session = |id: u64| {
+    let mut transcript = Transcript::new(b"session");
+    transcript.append(b"id", &id).unwrap();
+    transcript
+  };
+  let proof = ctx.aggregate_across_commitments_with_transcript(&openings, proofs.clone(), &mut session(1)).unwrap();
+  assert!(ctx.verify_across_commitments_with_transcript(&openings, &proof, &mut session(1)).unwrap());
+  assert!(!ctx.verify_across_commitments_with_transcript(&openings, &proof, &mut session(2)).unwrap());
+  assert!(!ctx.verify_across_commitments(&openings, &proof).unwrap());
+  assert!(ctx.verify_across_commitments(&openings, &ctx.aggregate_across_commitments(&openings, proofs).unwrap()).unwrap());
+
+  // a transcript also serves as the rng of the existing APIs
+  assert!(batch.verify(&params.verifying_key, ctx.omega(), &mut session(3)).unwrap() == vec![2]);
+  assert!(ctx.verify_all_upks(&params.proving_key, &mut session(4)).unwrap());
+}
+
+fn test_validate<E: PairingEngine>(){
+  use ark_ec::{AffineCurve, ProjectiveCurve};
+  use asvc::{CustomError, ParameterCheck};
+  let rng = &mut test_rng();
+  let size: usize = 8;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let hiding = asvc::key_gen_hiding::<E,_>(size, rng).unwrap();
+  assert!(params.validate().is_ok());
+  assert!(hiding.validate().is_ok());
+  assert!(params.trim(4, 2).unwrap().validate().is_ok());
+  assert!(hiding.trim(8, 1).unwrap().validate().is_ok());
+  assert!(asvc::Asvc::new(params.clone()).unwrap().validate().is_ok());
+  assert!(asvc::Asvc::from_verifying_key(params.verifying_key.clone()).unwrap().validate().is_ok());
+
+  let fails = |params: &asvc::Parameters<E>, check: ParameterCheck| {
+    match params.validate() {
+      Err(CustomError::InvalidParameters(found)) => assert_eq!(found, check),
+      _ => panic!("expected the {:?} check to fail", check),
+    }
+  };
+  let double = |p: <E as ark_ec::PairingEngine>::G1Affine| p.into_projective().double().into_affine();
+
+  // one wrong power in G1, in G2, in both keys
+  let mut tampered = params.clone();
+  tampered.verifying_key.list_g1_tau_i[3] = double(tampered.verifying_key.list_g1_tau_i[3]);
+  tampered.proving_key.list_g1_tau_i[3] = tampered.verifying_key.list_g1_tau_i[3];
+  fails(&tampered, ParameterCheck::Powers);
+  let mut tampered = params.clone();
+  let g2 = tampered.verifying_key.list_g2_tau_i[5];
+  tampered.verifying_key.list_g2_tau_i[5] = g2.into_projective().double().into_affine();
+  fails(&tampered, ParameterCheck::Powers);
+  // only in the proving key, past the verifying key powers
+  let mut tampered = params.trim(8, 2).unwrap();
+  tampered.proving_key.list_g1_tau_i[6] = double(tampered.proving_key.list_g1_tau_i[6]);
+  fails(&tampered, ParameterCheck::Powers);
+
+  let mut tampered = params.clone();
+  tampered.verifying_key.a = double(tampered.verifying_key.a);
+  fails(&tampered, ParameterCheck::A);
+  let mut tampered = params.trim(8, 2).unwrap();
+  tampered.verifying_key.a = double(tampered.verifying_key.a);
+  assert!(tampered.verifying_key.validate().is_ok());
+  fails(&tampered, ParameterCheck::A);
+
+  let mut tampered = params.clone();
+  tampered.proving_key.list_l_i.swap(1, 2);
+  fails(&tampered, ParameterCheck::LagrangeBases);
+
+  let mut tampered = params.clone();
+  tampered.proving_key.list_update_keys[4].u_i = double(tampered.proving_key.list_update_keys[4].u_i);
+  fails(&tampered, ParameterCheck::UpdateKeys);
+
+  let mut tampered = hiding.clone();
+  let key = tampered.proving_key.hiding_key.as_mut().unwrap();
+  key.list_h_l_i[0] = double(key.list_h_l_i[0]);
+  fails(&tampered, ParameterCheck::HidingKey);
+  let mut tampered = hiding.clone();
+  let h = tampered.verifying_key.list_h_tau_i.as_mut().unwrap();
+  h[2] = double(h[2]);
+  tampered.proving_key.hiding_key.as_mut().unwrap().list_h_tau_i[2] = h[2];
+  fails(&tampered, ParameterCheck::HidingKey);
+
+  // keys of two setups, and a hiding proving key with a plain verifying key
+  let other = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let mixed = asvc::Parameters { proving_key: other.proving_key, verifying_key: params.verifying_key.clone() };
+  fails(&mixed, ParameterCheck::Mismatch);
+  let mixed = asvc::Parameters { proving_key: hiding.proving_key, verifying_key: params.verifying_key.clone() };
+  fails(&mixed, ParameterCheck::Mismatch);
+
+  // τ = ω: a is the identity and the trapdoor public, though every other check holds
+  let omega = group_gen(&GeneralEvaluationDomain::<E::Fr>::new(size).unwrap());
+  let (g1, g2) = powers_of::<E>(omega, size);
+  let vrk = asvc::VerifyingKey::<E> {
+    list_g1_tau_i: g1.clone(),
+    list_g2_tau_i: g2,
+    a: ark_ff::Zero::zero(),
+    n: size as u64,
+    list_h_tau_i: None,
+  };
+  let prk = asvc::ProvingKey::<E> {
+    list_l_i: asvc::lagrange_keys::<E>(&g1).unwrap(),
+    list_update_keys: asvc::update_keys::<E>(&g1).unwrap(),
+    list_g1_tau_i: g1,
+    hiding_key: None,
+  };
+  assert!(matches!(vrk.validate(), Err(CustomError::InvalidParameters(ParameterCheck::A))));
+  fails(&asvc::Parameters { proving_key: prk, verifying_key: vrk.clone() }, ParameterCheck::A);
+  let mut trimmed = vrk;
+  trimmed.list_g1_tau_i.truncate(2);
+  trimmed.list_g2_tau_i.truncate(3);
+  assert!(matches!(trimmed.validate(), Err(CustomError::InvalidParameters(ParameterCheck::A))));
+
+  // wrong shapes are malformed keys, not failed checks
+  let mut tampered = params.clone();
+  tampered.proving_key.list_l_i.pop();
+  assert!(matches!(tampered.validate(), Err(CustomError::MalformedKey)));
+}
+
+fn test_vector_store<E: PairingEngine>(){
+  use asvc::{CustomError, VectorStore};
+  let rng = &mut test_rng();
+  let size: usize = 8;
+  let params = asvc::key_gen::<E,_>(size, rng).unwrap();
+  let ctx = asvc::Asvc::new(params.clone()).unwrap();
+
+  // a short vector is padded with zeros
+  let mut values: Vec<E::Fr> = (0..6).map(|_| E::Fr::rand(rng)).collect();
+  let mut store = VectorStore::from_parameters(params.clone(), values.clone()).unwrap();
+  values.resize(size, E::Fr::from(0u64));
+  assert_eq!(store.len(), size);
+  assert!(store.values() == &values[..]);
+  assert!(store.commitment() == &ctx.commit(values.clone()).unwrap());
+
+  let (point_values, proof) = store.prove(vec![1, 4, 7]).unwrap();
+  assert!(point_values == vec![values[1], values[4], values[7]]);
+  assert!(store.verify(point_values, vec![1, 4, 7], &proof).unwrap());
+  assert_eq!(store.cached_points(), vec![1, 4, 7]);
+
+  // every set keeps the commitment and the cached proofs equal to recomputed ones
+  for (point, value) in [(4u32, E::Fr::rand(rng)), (0, E::Fr::rand(rng)), (7, E::Fr::rand(rng)), (4, E::Fr::rand(rng))].iter() {
+    store.set(*point, *value).unwrap();
+    values[*point as usize] = *value;
+    assert!(store.get(*point) == Some(*value));
+    assert!(store.commitment() == &ctx.commit(values.clone()).unwrap());
+    for point_j in store.cached_points() {
+      assert!(store.proof(point_j).unwrap() == ctx.prove_pos(values.clone(), vec![point_j]).unwrap());
+    }
+  }
+  let (point_values, proof) = store.prove(vec![0, 4, 5]).unwrap();
+  assert!(ctx.verify_pos(store.commitment(), point_values, vec![0, 4, 5], &proof).unwrap());
+  assert!(proof == ctx.prove_pos(values.clone(), vec![0, 4, 5]).unwrap());
+
+  // all proofs at once, still updated by set
+  store.cache_all().unwrap();
+  assert_eq!(store.cached_points().len(), size);
+  store.set(2, E::Fr::rand(rng)).unwrap();
+  values[2] = store.get(2).unwrap();
+  let (point_values, proof) = store.prove(vec![2, 3, 6]).unwrap();
+  assert!(store.verify(point_values, vec![2, 3, 6], &proof).unwrap());
+  store.clear_cache();
+  assert!(store.cached_points().is_empty());
+
+  // out of range positions are errors and leave the store unchanged
+  let commitment = store.commitment().clone();
+  assert!(matches!(store.set(8, E::Fr::rand(rng)), Err(CustomError::IndexOutOfRange { index: 8, size: 8 })));
+  assert!(matches!(store.prove(vec![1, 9]), Err(CustomError::IndexOutOfRange { .. })));
+  assert!(matches!(store.prove(vec![3, 3]), Err(CustomError::DuplicateIndex(3))));
+  assert!(store.commitment() == &commitment && store.values() == &values[..]);
+
+  // a verifier only context cannot hold a store
+  let verifier = asvc::Asvc::from_verifying_key(params.verifying_key).unwrap();
+  assert!(matches!(VectorStore::new(verifier, values), Err(CustomError::MissingProvingKey)));
+}